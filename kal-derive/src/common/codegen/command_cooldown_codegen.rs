@@ -0,0 +1,20 @@
+use quote::quote;
+
+pub struct CommandCooldown {
+    pub millis: u64,
+    pub per: String,
+}
+
+impl CommandCooldown {
+    pub fn spec(cooldown: &Option<CommandCooldown>) -> quote::__private::TokenStream {
+        match cooldown {
+            Some(CommandCooldown { millis, per }) => quote! {
+                ::std::option::Option::Some(::kal::CommandCooldown {
+                    duration: ::std::time::Duration::from_millis(#millis),
+                    per: #per,
+                })
+            },
+            None => quote! { ::std::option::Option::None },
+        }
+    }
+}
@@ -1,21 +1,138 @@
+use darling::{util::PathList, FromField};
 use quote::{format_ident, quote, ToTokens};
-use syn::{Expr, Ident, Type};
+use syn::{Expr, Field, Ident, LitStr, Path, Type};
+
+use crate::common::{
+    config::argument_config::{quote_argument_paths, ArgumentConfig},
+    doc_string::join_doc_string,
+    error::{self, Error},
+};
 
 pub struct CommandOption {
     pub ident: Ident,
     pub name: String,
-    pub position: usize,
+    pub position: quote::__private::TokenStream,
     pub description: String,
     pub ty: Type,
     pub take_rest: bool,
+    pub variadic: bool,
+    pub flatten: bool,
     pub default: Option<Expr>,
+    pub validation: OptionValidation,
+    pub requires: Vec<Path>,
+    pub completion_provider: Option<Path>,
+}
+
+pub struct OptionValidation {
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub pattern: Option<LitStr>,
+    pub validate: Option<Path>,
+    pub choices: Vec<OptionChoice>,
+}
+
+pub enum OptionChoice {
+    String(String),
+    Integer(i64),
+    Float(f64),
+}
+
+impl ToTokens for OptionChoice {
+    fn to_tokens(&self, tokens: &mut quote::__private::TokenStream) {
+        tokens.extend(match self {
+            OptionChoice::String(s) => {
+                quote! { ::kal::CommandArgumentValue::String(#s.to_string()) }
+            }
+            OptionChoice::Integer(i) => quote! { ::kal::CommandArgumentValue::I64(#i) },
+            OptionChoice::Float(f) => quote! { ::kal::CommandArgumentValue::F64(#f) },
+        });
+    }
+}
+
+impl OptionValidation {
+    fn is_checked(&self) -> bool {
+        self.min_value.is_some()
+            || self.max_value.is_some()
+            || self.min_length.is_some()
+            || self.max_length.is_some()
+            || self.pattern.is_some()
+            || !self.choices.is_empty()
+    }
+
+    fn spec(&self) -> quote::__private::TokenStream {
+        fn option<T: ToTokens>(value: &Option<T>) -> quote::__private::TokenStream {
+            match value {
+                Some(value) => quote! { ::std::option::Option::Some(#value) },
+                None => quote! { ::std::option::Option::None },
+            }
+        }
+        let min_value = option(&self.min_value);
+        let max_value = option(&self.max_value);
+        let min_length = option(&self.min_length);
+        let max_length = option(&self.max_length);
+        // The field only exists with `regex` feature, which a pattern given requires.
+        let pattern = match &self.pattern {
+            Some(pattern) => quote! {
+                pattern: ::std::option::Option::Some(::kal::CommandOptionPattern::new(#pattern)),
+            },
+            None => quote! { ..::std::default::Default::default() },
+        };
+        let choices = &self.choices;
+        quote! {
+            ::kal::CommandOptionValidation {
+                min_value: #min_value,
+                max_value: #max_value,
+                min_length: #min_length,
+                max_length: #max_length,
+                choices: ::std::vec![#(#choices),*],
+                #pattern
+            }
+        }
+    }
 }
 
 impl CommandOption {
+    /// Read the option from the named field with its `#[argument(...)]` attributes.
+    pub fn from_field(
+        field: Field,
+        position: quote::__private::TokenStream,
+    ) -> error::Result<Self> {
+        let field = quote_argument_paths(field)?;
+        let argument_config = ArgumentConfig::from_field(&field)?;
+        let validation = argument_config.validation(&field.ty)?;
+        let ident = field
+            .ident
+            .clone()
+            .ok_or_else(|| Error::new(&field, "field must have a name"))?;
+        Ok(CommandOption {
+            name: argument_config.rename_or(&ident),
+            ident,
+            position,
+            description: join_doc_string(&field.attrs),
+            ty: field.ty,
+            take_rest: argument_config.take_rest,
+            variadic: argument_config.variadic.unwrap_or(false),
+            flatten: argument_config.flatten.unwrap_or(false),
+            validation,
+            requires: argument_config.requires,
+            completion_provider: argument_config.complete,
+            default: argument_config.default,
+        })
+    }
+
     pub fn declaration(&self) -> quote::__private::TokenStream {
         let Self {
-            ident, ty, default, ..
+            ident,
+            ty,
+            flatten,
+            default,
+            ..
         } = self;
+        if *flatten {
+            return quote! {};
+        }
         let ident = format_ident!("{}_field", ident);
         let default = default
             .as_ref()
@@ -26,46 +143,134 @@ impl CommandOption {
         }
     }
 
-    pub fn kal_option(&self) -> quote::__private::TokenStream {
+    pub fn kal_option(&self, requires: &[&String]) -> quote::__private::TokenStream {
         let Self {
             name,
             position,
             description,
             ty,
+            take_rest,
+            variadic,
+            validation,
+            completion_provider,
             ..
         } = self;
+        let validation = validation.spec();
+        let completion_provider = match completion_provider {
+            Some(provider) => quote! {
+                ::std::option::Option::Some(&#provider as &'static dyn ::kal::CompletionProvider)
+            },
+            None => quote! { ::std::option::Option::None },
+        };
         quote! {
             ::kal::CommandOption {
                 name: #name,
                 position: #position,
                 description: #description,
                 value: <#ty as ::kal::CommandOptionValueTy>::spec_kind(),
+                take_rest: #take_rest,
+                variadic: #variadic,
+                validation: #validation,
+                requires: ::std::vec![#(#requires),*],
+                completion_provider: #completion_provider,
             }
         }
     }
 
-    pub fn match_arms(&self) -> (quote::__private::TokenStream, quote::__private::TokenStream) {
+    pub fn match_arms(
+        &self,
+    ) -> Option<(quote::__private::TokenStream, quote::__private::TokenStream)> {
         let Self {
             ident,
             name,
             position,
             ty,
+            variadic,
+            flatten,
+            validation,
             ..
         } = self;
+        if *flatten {
+            return None;
+        }
         let ident = format_ident!("{}_field", ident);
+        // Validate the converted value, each element of it one by one.
+        let check = |converted: quote::__private::TokenStream| {
+            if !validation.is_checked() {
+                return quote! {};
+            }
+            let spec = validation.spec();
+            quote! {
+                static VALIDATION: ::std::sync::OnceLock<::kal::CommandOptionValidation> = ::std::sync::OnceLock::new();
+                let validation = VALIDATION.get_or_init(|| #spec);
+                for value in ::kal::ToArgumentValue::to_argument_values(#converted) {
+                    if let ::std::result::Result::Err(reason) = validation.check(&value) {
+                        return ::std::result::Result::Err(::kal::CommandParseError::ValidationFailed {
+                            argument: #name,
+                            reason,
+                        });
+                    }
+                }
+            }
+        };
+        if *variadic {
+            let check = check(quote! { &element });
+            let push = quote! {
+                #ident = match (
+                    #ident,
+                    kal::TryFromArgumentValue::try_from_argument_value(value.clone(), true).ok(),
+                ) {
+                    (::std::option::Option::Some(mut values), ::std::option::Option::Some(element)) => {
+                        #check
+                        values.push(element);
+                        ::std::option::Option::Some(values)
+                    }
+                    _ => ::std::option::Option::None,
+                }
+            };
+            return Some((
+                quote! { #name => { #push } },
+                quote! { position if *position >= #position => { #push } },
+            ));
+        }
         let value = quote! {
             <#ty as kal::TryFromArgumentValue>::try_from_argument_value(value.clone(), true).ok()
         };
-        let assignment = quote! { #ident = #value };
-        (
+        let check = check(quote! { &#ident });
+        let assignment = quote! {
+            {
+                #ident = #value;
+                #check
+            }
+        };
+        Some((
             quote! { #name => #assignment },
-            quote! { #position => #assignment },
-        )
+            quote! { position if *position == #position => #assignment },
+        ))
     }
 
     pub fn check_missed<T: ToTokens>(&self, collection_name: T) -> quote::__private::TokenStream {
-        let Self { ident, name, .. } = self;
+        let Self {
+            ident,
+            name,
+            position,
+            ty,
+            flatten,
+            ..
+        } = self;
         let ident = format_ident!("{}_field", ident);
+        if *flatten {
+            return quote! {
+                let #ident = match <#ty as ::kal::Arguments>::from_arguments(arguments, #position) {
+                    ::std::result::Result::Ok(value) => ::std::option::Option::Some(value),
+                    ::std::result::Result::Err(::kal::CommandParseError::MissingArguments(missing)) => {
+                        #collection_name.extend(missing);
+                        ::std::option::Option::None
+                    }
+                    ::std::result::Result::Err(error) => return ::std::result::Result::Err(error),
+                };
+            };
+        }
         quote! {
             if #ident.is_none() {
                 #collection_name.push(#name);
@@ -73,30 +278,183 @@ impl CommandOption {
         }
     }
 
+    pub fn check_validate(&self) -> quote::__private::TokenStream {
+        let Self {
+            ident,
+            name,
+            validation,
+            ..
+        } = self;
+        let Some(validate) = &validation.validate else {
+            return quote! {};
+        };
+        let ident = format_ident!("{}_field", ident);
+        quote! {
+            if let ::std::option::Option::Some(value) = &#ident {
+                if let ::std::result::Result::Err(reason) = #validate(value) {
+                    return ::std::result::Result::Err(::kal::CommandParseError::ValidationFailed {
+                        argument: #name,
+                        reason: ::std::string::ToString::to_string(&reason),
+                    });
+                }
+            }
+        }
+    }
+
     pub fn transform_hint_part(&self) -> quote::__private::TokenStream {
-        let Self { ty, take_rest, .. } = self;
+        let Self {
+            name,
+            ty,
+            take_rest,
+            variadic,
+            ..
+        } = self;
         let make_greedy = if *take_rest {
             quote! { .make_greedy() }
+        } else if *variadic {
+            quote! { .make_variadic() }
         } else {
             quote! {}
         };
-        quote! { ::kal::lex::TransformHintPart::from(<#ty as ::kal::CommandOptionValueTy>::spec_kind()) #make_greedy }
+        quote! { (#name, ::kal::lex::TransformHintPart::from(<#ty as ::kal::CommandOptionValueTy>::spec_kind()) #make_greedy) }
     }
 }
 
 pub trait CommandOptionsExt {
+    fn check_variadic(&self) -> error::Result<()>;
+
+    fn check_relations(&self, exclusive: &[PathList]) -> error::Result<()>;
+
+    fn find_option(&self, path: &Path) -> Option<&CommandOption>;
+
+    fn next_position(&self) -> quote::__private::TokenStream;
+
     fn build_struct<T: ToTokens, U: ToTokens>(
         &self,
         name: T,
         missing_arguments: U,
     ) -> quote::__private::TokenStream;
 
-    fn make_execute_work<T: ToTokens>(&self, name: T) -> quote::__private::TokenStream;
+    fn make_execute_work<T: ToTokens>(
+        &self,
+        name: T,
+        exclusive: &[PathList],
+    ) -> quote::__private::TokenStream;
+
+    fn make_relations_check(&self, exclusive: &[PathList]) -> quote::__private::TokenStream;
+
+    fn make_exclusive_spec(&self, exclusive: &[PathList]) -> quote::__private::TokenStream;
+
+    fn make_options_spec(&self) -> quote::__private::TokenStream;
 
     fn make_transform_hint_vec(&self) -> quote::__private::TokenStream;
+
+    fn make_fields_pattern(&self) -> quote::__private::TokenStream;
+
+    fn make_argument_values(&self) -> quote::__private::TokenStream;
+
+    fn make_execute_fragment(&self) -> quote::__private::TokenStream;
+}
+
+/// Collect items of options into a `Vec`, splicing the segment of each option `is_segment` is true.
+fn collect_segments(
+    options: &[CommandOption],
+    is_segment: impl Fn(&CommandOption) -> bool,
+    item: impl Fn(&CommandOption) -> quote::__private::TokenStream,
+    segment: impl Fn(&CommandOption) -> quote::__private::TokenStream,
+) -> quote::__private::TokenStream {
+    if !options.iter().any(&is_segment) {
+        let items = options.iter().map(item);
+        return quote! { ::std::vec![#(#items),*] };
+    }
+    let segments = options
+        .split_inclusive(&is_segment)
+        .flat_map(|chunk| {
+            let (last, init) = chunk.split_last().unwrap();
+            let (init, flattened) = if is_segment(last) {
+                (init, Some(segment(last)))
+            } else {
+                (chunk, None)
+            };
+            let items = init.iter().map(&item);
+            [Some(quote! { [#(#items),*] }), flattened]
+        })
+        .flatten();
+    quote! {
+        ::std::iter::empty()
+            #(.chain(#segments))*
+            .collect::<::std::vec::Vec<_>>()
+    }
+}
+
+/// Whether the type is spelled as `Vec<T>`, which variadic arguments collect into.
+fn is_vec(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Vec"),
+        _ => false,
+    }
 }
 
 impl CommandOptionsExt for Vec<CommandOption> {
+    fn check_variadic(&self) -> error::Result<()> {
+        for (i, option) in self.iter().enumerate() {
+            if !option.variadic {
+                continue;
+            }
+            if !is_vec(&option.ty) {
+                return Err(Error::new(&option.ty, "Variadic argument must be a `Vec`"));
+            }
+            if option.take_rest {
+                return Err(Error::new(
+                    &option.ident,
+                    "Variadic argument cannot take the rest",
+                ));
+            }
+            if i + 1 != self.len() {
+                return Err(Error::new(
+                    &option.ident,
+                    "Only the last argument can be variadic",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_relations(&self, exclusive: &[PathList]) -> error::Result<()> {
+        let paths = exclusive
+            .iter()
+            .flat_map(|group| group.iter())
+            .chain(self.iter().flat_map(|option| &option.requires));
+        for path in paths {
+            if self.find_option(path).is_none() {
+                return Err(Error::new(path, "Unknown argument"));
+            }
+        }
+        Ok(())
+    }
+
+    fn find_option(&self, path: &Path) -> Option<&CommandOption> {
+        self.iter()
+            .find(|option| !option.flatten && path.is_ident(&option.ident))
+    }
+
+    fn next_position(&self) -> quote::__private::TokenStream {
+        let plain = self.iter().filter(|option| !option.flatten).count();
+        let mut flattened = self
+            .iter()
+            .filter(|option| option.flatten)
+            .map(|option| &option.ty)
+            .peekable();
+        if flattened.peek().is_none() {
+            return quote! { #plain };
+        }
+        quote! { (#plain #(+ <#flattened as ::kal::Arguments>::COUNT)*) }
+    }
+
     fn build_struct<T: ToTokens, U: ToTokens>(
         &self,
         name: T,
@@ -120,10 +478,14 @@ impl CommandOptionsExt for Vec<CommandOption> {
         }
     }
 
-    fn make_execute_work<T: ToTokens>(&self, name: T) -> quote::__private::TokenStream {
+    fn make_execute_work<T: ToTokens>(
+        &self,
+        name: T,
+        exclusive: &[PathList],
+    ) -> quote::__private::TokenStream {
         let options_declaration: Vec<_> = self.iter().map(|opt| opt.declaration()).collect();
         let (options_match_arm_named, options_match_arm_positioned): (Vec<_>, Vec<_>) =
-            self.iter().map(|opt| opt.match_arms()).unzip();
+            self.iter().filter_map(|opt| opt.match_arms()).unzip();
         let options_check_missed: Vec<_> = self
             .iter()
             .map(|opt| {
@@ -132,6 +494,8 @@ impl CommandOptionsExt for Vec<CommandOption> {
                 })
             })
             .collect();
+        let options_check_relations = self.make_relations_check(exclusive);
+        let options_check_validate: Vec<_> = self.iter().map(|opt| opt.check_validate()).collect();
         let options_build_struct = self.build_struct(
             name,
             quote! {
@@ -162,18 +526,180 @@ impl CommandOptionsExt for Vec<CommandOption> {
                     }
                 }
 
+                #options_check_relations
+
                 #(#options_check_missed)*
 
+                #(#options_check_validate)*
+
                 #options_build_struct
             }
         }
     }
 
+    fn make_relations_check(&self, exclusive: &[PathList]) -> quote::__private::TokenStream {
+        let provided = |option: &CommandOption| {
+            let CommandOption { name, position, .. } = option;
+            quote! { provided(#name, #position) }
+        };
+        let exclusive_checks = exclusive.iter().map(|group| {
+            let group = group.iter().filter_map(|path| self.find_option(path));
+            let (names, provided): (Vec<_>, Vec<_>) =
+                group.map(|option| (&option.name, provided(option))).unzip();
+            quote! {
+                let conflicting: ::std::vec::Vec<&str> = [#((#names, #provided)),*]
+                    .into_iter()
+                    .filter(|(_, provided)| *provided)
+                    .map(|(name, _)| name)
+                    .collect();
+                if conflicting.len() > 1 {
+                    return ::std::result::Result::Err(::kal::CommandParseError::ConflictingArguments(conflicting));
+                }
+            }
+        });
+        let requires_checks = self.iter().flat_map(|option| {
+            let name = &option.name;
+            let option_provided = provided(option);
+            option
+                .requires
+                .iter()
+                .filter_map(|path| self.find_option(path))
+                .map(move |required| {
+                    let required_name = &required.name;
+                    let required_provided = provided(required);
+                    quote! {
+                        if #option_provided && !#required_provided {
+                            return ::std::result::Result::Err(::kal::CommandParseError::MissingRequiredArgument {
+                                argument: #name,
+                                requires: #required_name,
+                            });
+                        }
+                    }
+                })
+        });
+        let checks: Vec<_> = exclusive_checks.chain(requires_checks).collect();
+        if checks.is_empty() {
+            return quote! {};
+        }
+
+        quote! {
+            let provided = |name: &str, position: usize| {
+                arguments.iter().any(|argument| match argument {
+                    ::kal::CommandArgument::Named(argument_name, _) => argument_name == name,
+                    ::kal::CommandArgument::Positioned(argument_position, _) => *argument_position == position,
+                })
+            };
+            #({ #checks })*
+        }
+    }
+
+    fn make_exclusive_spec(&self, exclusive: &[PathList]) -> quote::__private::TokenStream {
+        let groups = exclusive.iter().map(|group| {
+            let names = group
+                .iter()
+                .filter_map(|path| self.find_option(path))
+                .map(|option| &option.name);
+            quote! { ::std::vec![#(#names),*] }
+        });
+        quote! { ::std::vec![#(#groups),*] }
+    }
+
+    fn make_options_spec(&self) -> quote::__private::TokenStream {
+        collect_segments(
+            self,
+            |opt| opt.flatten,
+            |opt| {
+                let requires: Vec<_> = opt
+                    .requires
+                    .iter()
+                    .filter_map(|path| self.find_option(path))
+                    .map(|required| &required.name)
+                    .collect();
+                opt.kal_option(&requires)
+            },
+            |opt| {
+                let CommandOption { ty, position, .. } = opt;
+                quote! { <#ty as ::kal::Arguments>::options(#position) }
+            },
+        )
+    }
+
     fn make_transform_hint_vec(&self) -> quote::__private::TokenStream {
-        let parts: Vec<_> = self.iter().map(|opt| opt.transform_hint_part()).collect();
+        collect_segments(
+            self,
+            |opt| opt.flatten,
+            |opt| opt.transform_hint_part(),
+            |opt| {
+                let CommandOption { ty, position, .. } = opt;
+                quote! {
+                    <#ty as ::kal::Arguments>::options(#position)
+                        .iter()
+                        .map(|option| (option.name, ::kal::lex::TransformHintPart::from(option)))
+                }
+            },
+        )
+    }
+
+    fn make_fields_pattern(&self) -> quote::__private::TokenStream {
+        let idents: Vec<_> = self.iter().map(|option| &option.ident).collect();
+        let idents_field: Vec<_> = self
+            .iter()
+            .map(|option| format_ident!("{}_field", option.ident))
+            .collect();
 
         quote! {
-            vec![#(#parts),*]
+            { #(#idents: #idents_field),* }
+        }
+    }
+
+    fn make_argument_values(&self) -> quote::__private::TokenStream {
+        collect_segments(
+            self,
+            |opt| opt.flatten || opt.variadic,
+            |opt| {
+                let name = &opt.name;
+                let ident_field = format_ident!("{}_field", opt.ident);
+                quote! { (#name, ::kal::ToArgumentValue::to_argument_value(#ident_field)) }
+            },
+            |opt| {
+                let name = &opt.name;
+                let ident_field = format_ident!("{}_field", opt.ident);
+                if opt.variadic {
+                    quote! {
+                        #ident_field
+                            .iter()
+                            .map(|value| (#name, ::kal::ToArgumentValue::to_argument_value(value)))
+                    }
+                } else {
+                    quote! { ::kal::Arguments::to_argument_values(#ident_field) }
+                }
+            },
+        )
+    }
+
+    fn make_execute_fragment(&self) -> quote::__private::TokenStream {
+        let values = self.make_argument_values();
+
+        quote! {
+            {
+                let values: ::std::vec::Vec<(&str, ::std::option::Option<::kal::CommandArgumentValue>)> = #values;
+
+                let mut arguments = ::std::vec::Vec::new();
+                let mut named = false;
+                for (position, (name, value)) in values.into_iter().enumerate() {
+                    match value {
+                        ::std::option::Option::Some(value) if named => {
+                            arguments.push(::kal::CommandArgument::Named(name.to_string(), value))
+                        }
+                        ::std::option::Option::Some(value) => {
+                            arguments.push(::kal::CommandArgument::Positioned(position, value))
+                        }
+                        ::std::option::Option::None => named = true,
+                    }
+                }
+
+                ::kal::CommandFragment::Execute(arguments)
+            }
         }
     }
 }
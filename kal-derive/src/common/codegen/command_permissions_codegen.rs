@@ -0,0 +1,26 @@
+use quote::quote;
+
+#[derive(Default)]
+pub struct CommandPermissions {
+    pub required: Vec<String>,
+    pub owner_only: bool,
+}
+
+impl CommandPermissions {
+    pub fn is_empty(&self) -> bool {
+        self.required.is_empty() && !self.owner_only
+    }
+
+    pub fn spec(&self) -> quote::__private::TokenStream {
+        let Self {
+            required,
+            owner_only,
+        } = self;
+        quote! {
+            ::kal::CommandPermissions {
+                required: ::std::vec![#(#required),*],
+                owner_only: #owner_only,
+            }
+        }
+    }
+}
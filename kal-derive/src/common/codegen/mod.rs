@@ -1 +1,3 @@
+pub mod command_cooldown_codegen;
 pub mod command_option_codegen;
+pub mod command_permissions_codegen;
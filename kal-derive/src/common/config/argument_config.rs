@@ -1,7 +1,18 @@
 use darling::FromField;
-use syn::{Expr, Ident};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Expr, Field, GenericArgument, Ident, Lit, LitStr, NestedMeta, Path, PathArguments, Token, Type,
+    TypePath,
+};
 
-use crate::common::renamer::rename_to_kebab_case;
+use crate::common::{
+    codegen::command_option_codegen::{OptionChoice, OptionValidation},
+    error::{self, Error},
+    renamer::rename_to_kebab_case,
+};
 
 #[derive(FromField)]
 #[darling(attributes(argument))]
@@ -9,8 +20,23 @@ pub struct ArgumentConfig {
     rename: Option<String>,
     #[darling(default)]
     pub take_rest: bool,
+    pub variadic: Option<bool>,
+    pub flatten: Option<bool>,
+    #[darling(multiple)]
+    pub requires: Vec<Path>,
 
     pub default: Option<Expr>,
+
+    min: Option<Lit>,
+    max: Option<Lit>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    pattern: Option<LitStr>,
+    validate: Option<Path>,
+    #[darling(multiple)]
+    choice: Vec<Lit>,
+
+    pub complete: Option<Path>,
 }
 
 impl ArgumentConfig {
@@ -19,4 +45,146 @@ impl ArgumentConfig {
             .clone()
             .unwrap_or_else(|| rename_to_kebab_case(ident.to_string()))
     }
+
+    /// Read the validation, refusing the bounds which the field of type `ty` can never be checked with.
+    pub fn validation(&self, ty: &Type) -> error::Result<OptionValidation> {
+        let kind = value_kind(ty);
+        if kind == ValueKind::String {
+            if let Some(lit) = self.min.as_ref().or(self.max.as_ref()) {
+                return Err(Error::new(lit, "min and max are only for numeric fields"));
+            }
+        }
+        if kind == ValueKind::Number && (self.min_length.is_some() || self.max_length.is_some()) {
+            return Err(Error::new(
+                ty,
+                "min_length and max_length are only for string fields",
+            ));
+        }
+        Ok(OptionValidation {
+            min_value: self.min.as_ref().map(number_literal).transpose()?,
+            max_value: self.max.as_ref().map(number_literal).transpose()?,
+            min_length: self.min_length,
+            max_length: self.max_length,
+            pattern: self.pattern.as_ref().map(pattern_literal).transpose()?,
+            validate: self.validate.clone(),
+            choices: self
+                .choice
+                .iter()
+                .map(choice_literal)
+                .collect::<error::Result<_>>()?,
+        })
+    }
+}
+
+#[derive(PartialEq)]
+enum ValueKind {
+    Number,
+    String,
+    /// The custom types, whose kind is known only when the program runs.
+    Unknown,
+}
+
+/// Tell the kind of values the field holds, looking through `Option` and the collections.
+fn value_kind(ty: &Type) -> ValueKind {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return ValueKind::Unknown;
+    };
+    let Some(segment) = path.segments.last() else {
+        return ValueKind::Unknown;
+    };
+    match (segment.ident.to_string().as_str(), &segment.arguments) {
+        ("i64" | "f64", PathArguments::None) => ValueKind::Number,
+        ("String", PathArguments::None) => ValueKind::String,
+        (
+            "Option" | "Vec" | "SpaceSeparated" | "CommaSeparated",
+            PathArguments::AngleBracketed(arguments),
+        ) => match arguments.args.first() {
+            Some(GenericArgument::Type(inner)) => value_kind(inner),
+            _ => ValueKind::Unknown,
+        },
+        _ => ValueKind::Unknown,
+    }
+}
+
+/// An item of `#[argument(...)]`, where `name = path` is read as well as the meta.
+enum ArgumentItem {
+    Meta(NestedMeta),
+    Path(Ident, Path),
+}
+
+impl Parse for ArgumentItem {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Ident)
+            && input.peek2(Token![=])
+            && (input.peek3(Ident) || input.peek3(Token![::]))
+        {
+            let name = input.parse()?;
+            input.parse::<Token![=]>()?;
+            Ok(ArgumentItem::Path(name, input.parse()?))
+        } else {
+            input.parse().map(ArgumentItem::Meta)
+        }
+    }
+}
+
+impl ToTokens for ArgumentItem {
+    fn to_tokens(&self, tokens: &mut quote::__private::TokenStream) {
+        match self {
+            ArgumentItem::Meta(meta) => meta.to_tokens(tokens),
+            ArgumentItem::Path(name, path) => {
+                let path = LitStr::new(&path.to_token_stream().to_string(), path.span());
+                tokens.extend(quote! { #name = #path });
+            }
+        }
+    }
+}
+
+/// Quote the paths like `validate = path::to::fn` in `#[argument(...)]`,
+/// as only literals can follow `=` in the meta darling reads.
+pub fn quote_argument_paths(mut field: Field) -> error::Result<Field> {
+    for attr in &mut field.attrs {
+        if attr.path.is_ident("argument") {
+            let items =
+                attr.parse_args_with(Punctuated::<ArgumentItem, Token![,]>::parse_terminated)?;
+            attr.tokens = quote! { (#items) };
+        }
+    }
+    Ok(field)
+}
+
+/// Read a number from integer, float or string literal, the last one is for negative numbers.
+fn number_literal(lit: &Lit) -> error::Result<f64> {
+    let parsed = match lit {
+        Lit::Int(i) => i.base10_parse(),
+        Lit::Float(f) => f.base10_parse(),
+        Lit::Str(s) => s
+            .value()
+            .parse()
+            .map_err(|_| syn::Error::new_spanned(s, "expected a number")),
+        _ => return Err(Error::new(lit, "expected a number")),
+    };
+    Ok(parsed?)
+}
+
+/// Read a choice from string, integer or float literal.
+fn choice_literal(lit: &Lit) -> error::Result<OptionChoice> {
+    Ok(match lit {
+        Lit::Str(s) => OptionChoice::String(s.value()),
+        Lit::Int(i) => OptionChoice::Integer(i.base10_parse()?),
+        Lit::Float(f) => OptionChoice::Float(f.base10_parse()?),
+        _ => return Err(Error::new(lit, "expected a string or number")),
+    })
+}
+
+/// Check the pattern compiles, so that an invalid one fails the build rather than every parse.
+#[cfg(feature = "regex")]
+fn pattern_literal(lit: &LitStr) -> error::Result<LitStr> {
+    regex::Regex::new(&lit.value())
+        .map_err(|e| Error::new(lit, format!("Invalid pattern: {}", e)))?;
+    Ok(lit.clone())
+}
+
+#[cfg(not(feature = "regex"))]
+fn pattern_literal(lit: &LitStr) -> error::Result<LitStr> {
+    Err(Error::new(lit, "Pattern requires `regex` feature of kal"))
 }
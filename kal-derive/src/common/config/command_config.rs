@@ -1,7 +1,13 @@
-use darling::{FromDeriveInput, FromVariant};
-use syn::Ident;
+use darling::{util::PathList, FromDeriveInput, FromVariant};
+use syn::{Ident, LitStr};
 
-use crate::common::renamer::rename_to_kebab_case;
+use crate::common::{
+    codegen::{
+        command_cooldown_codegen::CommandCooldown, command_permissions_codegen::CommandPermissions,
+    },
+    error::{self, Error},
+    renamer::rename_to_kebab_case,
+};
 
 #[derive(FromDeriveInput, FromVariant)]
 #[darling(attributes(command))]
@@ -10,6 +16,21 @@ pub struct CommandConfig {
 
     #[darling(rename = "self")]
     pub for_self: Option<bool>,
+
+    #[darling(multiple)]
+    pub exclusive: Vec<PathList>,
+
+    permissions: Option<String>,
+
+    owner_only: Option<bool>,
+
+    cooldown: Option<LitStr>,
+
+    per: Option<LitStr>,
+
+    pub confirm: Option<String>,
+
+    pub to_fragments: Option<bool>,
 }
 
 impl CommandConfig {
@@ -18,4 +39,60 @@ impl CommandConfig {
             .clone()
             .unwrap_or_else(|| rename_to_kebab_case(ident.to_string()))
     }
+
+    pub fn permissions(&self) -> CommandPermissions {
+        permissions(&self.permissions, self.owner_only)
+    }
+
+    pub fn cooldown(&self) -> error::Result<Option<CommandCooldown>> {
+        cooldown(&self.cooldown, &self.per)
+    }
+}
+
+pub fn permissions(permissions: &Option<String>, owner_only: Option<bool>) -> CommandPermissions {
+    CommandPermissions {
+        required: permissions
+            .iter()
+            .flat_map(|permissions| permissions.split(','))
+            .map(|permission| permission.trim().to_string())
+            .filter(|permission| !permission.is_empty())
+            .collect(),
+        owner_only: owner_only.unwrap_or(false),
+    }
+}
+
+pub fn cooldown(
+    cooldown: &Option<LitStr>,
+    per: &Option<LitStr>,
+) -> error::Result<Option<CommandCooldown>> {
+    let cooldown = match (cooldown, per) {
+        (Some(cooldown), _) => cooldown,
+        (None, Some(per)) => return Err(Error::new(per, "per requires cooldown")),
+        (None, None) => return Ok(None),
+    };
+    let millis = duration_millis(&cooldown.value())
+        .ok_or_else(|| Error::new(cooldown, "expected a duration like 500ms, 5s, 2m, 1h or 1d"))?;
+    Ok(Some(CommandCooldown {
+        millis,
+        per: per
+            .as_ref()
+            .map(LitStr::value)
+            .unwrap_or_else(|| "user".to_string()),
+    }))
+}
+
+/// Read a duration made of a number and a unit, like `5s`.
+fn duration_millis(duration: &str) -> Option<u64> {
+    let split = duration.find(|c: char| !c.is_ascii_digit())?;
+    let (number, unit) = duration.split_at(split);
+    let number: u64 = number.parse().ok()?;
+    let unit = match unit {
+        "ms" => 1,
+        "s" => 1000,
+        "m" => 60 * 1000,
+        "h" => 60 * 60 * 1000,
+        "d" => 24 * 60 * 60 * 1000,
+        _ => return None,
+    };
+    number.checked_mul(unit)
 }
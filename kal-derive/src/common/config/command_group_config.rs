@@ -0,0 +1,71 @@
+use darling::{FromDeriveInput, FromVariant};
+use syn::{Ident, LitStr};
+
+use crate::common::{
+    codegen::{
+        command_cooldown_codegen::CommandCooldown, command_permissions_codegen::CommandPermissions,
+    },
+    error,
+    renamer::rename_to_kebab_case,
+};
+
+use super::command_config::{cooldown, permissions};
+
+#[derive(FromDeriveInput)]
+#[darling(attributes(command))]
+pub struct CommandGroupConfig {
+    rename: Option<String>,
+
+    pub description: Option<String>,
+
+    permissions: Option<String>,
+
+    owner_only: Option<bool>,
+
+    pub to_fragments: Option<bool>,
+}
+
+impl CommandGroupConfig {
+    pub fn rename_or(&self, ident: &Ident) -> String {
+        self.rename
+            .clone()
+            .unwrap_or_else(|| rename_to_kebab_case(ident.to_string()))
+    }
+
+    pub fn permissions(&self) -> CommandPermissions {
+        permissions(&self.permissions, self.owner_only)
+    }
+}
+
+#[derive(FromVariant)]
+#[darling(attributes(command))]
+pub struct GroupMemberConfig {
+    pub rename: Option<String>,
+
+    pub description: Option<String>,
+
+    #[darling(multiple)]
+    pub alias: Vec<String>,
+
+    pub hint_provider: Option<bool>,
+
+    permissions: Option<String>,
+
+    owner_only: Option<bool>,
+
+    cooldown: Option<LitStr>,
+
+    per: Option<LitStr>,
+
+    pub confirm: Option<String>,
+}
+
+impl GroupMemberConfig {
+    pub fn permissions(&self) -> CommandPermissions {
+        permissions(&self.permissions, self.owner_only)
+    }
+
+    pub fn cooldown(&self) -> error::Result<Option<CommandCooldown>> {
+        cooldown(&self.cooldown, &self.per)
+    }
+}
@@ -1,2 +1,3 @@
 pub mod argument_config;
 pub mod command_config;
+pub mod command_group_config;
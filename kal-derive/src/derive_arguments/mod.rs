@@ -0,0 +1,81 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::DeriveInput;
+
+use crate::common::{
+    codegen::command_option_codegen::{CommandOption, CommandOptionsExt},
+    error::{self, Error},
+};
+
+pub fn actual_derive_arguments(derive_input: DeriveInput) -> error::Result<TokenStream> {
+    let name = derive_input.ident;
+
+    let data = match derive_input.data {
+        syn::Data::Struct(data) => data,
+        syn::Data::Enum(data) => {
+            return Err(Error::new(
+                data.enum_token,
+                "Cannot derive Arguments for enum",
+            ))
+        }
+        syn::Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token,
+                "Cannot derive Arguments for union",
+            ))
+        }
+    };
+
+    let mut options = Vec::new();
+    for field in data.fields {
+        options.push(CommandOption::from_field(field, options.next_position())?);
+    }
+
+    if let Some(option) = options.iter().find(|option| option.variadic) {
+        return Err(Error::new(
+            &option.ident,
+            "Variadic argument cannot be flattened",
+        ));
+    }
+
+    // Arguments take no exclusive groups, but the requirements still name the fields.
+    options.check_relations(&[])?;
+
+    let count = options.next_position();
+    for option in &mut options {
+        let position = &option.position;
+        option.position = quote! { offset + #position };
+    }
+
+    let options_kal = options.make_options_spec();
+    let arguments_work = options.make_execute_work(&name, &[]);
+    let fields_pattern = options.make_fields_pattern();
+    let argument_values = options.make_argument_values();
+
+    Ok(quote! {
+        impl ::kal::Arguments for #name {
+            const COUNT: usize = #count;
+
+            #[allow(unused_variables)]
+            fn options(offset: usize) -> ::std::vec::Vec<::kal::CommandOption> {
+                #options_kal
+            }
+
+            #[allow(unused_variables)]
+            fn from_arguments(
+                arguments: &[::kal::CommandArgument],
+                offset: usize,
+            ) -> ::std::result::Result<Self, ::kal::CommandParseError<'static>> {
+                #arguments_work
+            }
+
+            fn to_argument_values(
+                &self,
+            ) -> ::std::vec::Vec<(&'static str, ::std::option::Option<::kal::CommandArgumentValue>)> {
+                let #name #fields_pattern = self;
+                #argument_values
+            }
+        }
+    }
+    .into())
+}
@@ -5,12 +5,16 @@
 #![deny(missing_docs)]
 
 use crate::common::{
-    codegen::command_option_codegen::{CommandOption, CommandOptionsExt},
-    config::{argument_config::ArgumentConfig, command_config::CommandConfig},
+    codegen::{
+        command_cooldown_codegen::CommandCooldown,
+        command_option_codegen::{CommandOption, CommandOptionsExt},
+        command_permissions_codegen::CommandPermissions,
+    },
+    config::command_config::CommandConfig,
     doc_string::join_doc_string,
     error::{self, Error},
 };
-use darling::{FromDeriveInput, FromField, FromVariant};
+use darling::{FromDeriveInput, FromVariant};
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{DeriveInput, Fields};
@@ -19,14 +23,23 @@ pub fn actual_derive_command(derive_input: DeriveInput) -> error::Result<TokenSt
     let root_command_config = CommandConfig::from_derive_input(&derive_input)?;
     let root_command_name = root_command_config.rename_or(&derive_input.ident);
     let root_command_description = join_doc_string(&derive_input.attrs);
+    let permissions = root_command_config.permissions();
+    let mut self_permissions = CommandPermissions::default();
+    let cooldown = root_command_config.cooldown()?;
+    let mut self_cooldown = None;
+    let confirm = root_command_config.confirm.clone();
+    let mut self_confirm = None;
+    let to_fragments = root_command_config.to_fragments.unwrap_or(false);
 
     let name = derive_input.ident;
 
     let mut options = Vec::new();
+    let mut exclusive = root_command_config.exclusive;
 
     let mut subcommands = Vec::new();
     let mut subcommands_named_fields_match_arms = Vec::new();
     let mut subcommand_match_arms = Vec::new();
+    let mut to_fragments_match_arms = Vec::new();
 
     let mut self_discovered = Vec::new();
 
@@ -36,78 +49,91 @@ pub fn actual_derive_command(derive_input: DeriveInput) -> error::Result<TokenSt
                 #name
             });
             for field in data.fields {
-                let argument_config = ArgumentConfig::from_field(&field)?;
-                let field_ident = field
-                    .ident
-                    .clone()
-                    .ok_or_else(|| Error::new(&field, "enum variant field must have a name"))?;
-                let argument_name = argument_config.rename_or(&field_ident);
-                let argument_description = join_doc_string(&field.attrs);
-
-                options.push(CommandOption {
-                    ident: field_ident,
-                    name: argument_name,
-                    position: options.len(),
-                    description: argument_description,
-                    ty: field.ty,
-                    take_rest: argument_config.take_rest,
-                    default: argument_config.default,
-                });
+                options.push(CommandOption::from_field(field, options.next_position())?);
             }
+            options.check_variadic()?;
         }
         syn::Data::Enum(data) => {
             for variant in data.variants {
                 let command_config = CommandConfig::from_variant(&variant);
+                if let Ok(CommandConfig {
+                    to_fragments: Some(_),
+                    ..
+                }) = &command_config
+                {
+                    return Err(Error::new(
+                        &variant.ident,
+                        "to_fragments is only for the command, not its variant",
+                    ));
+                }
 
                 match variant.fields {
                     Fields::Named(fields) => {
                         let mut inner_options = Vec::new();
                         for field in fields.named {
-                            let argument_config = ArgumentConfig::from_field(&field)?;
-                            let ident = field.ident.clone().ok_or_else(|| {
-                                Error::new(&field, "enum variant field must have a name")
-                            })?;
-
-                            let argument_name = argument_config.rename_or(&ident);
-                            let argument_description = join_doc_string(&field.attrs);
-                            inner_options.push(CommandOption {
-                                ident,
-                                name: argument_name,
-                                position: inner_options.len(),
-                                description: argument_description,
-                                ty: field.ty,
-                                take_rest: argument_config.take_rest,
-                                default: argument_config.default,
-                            });
+                            inner_options.push(CommandOption::from_field(
+                                field,
+                                inner_options.next_position(),
+                            )?);
                         }
 
+                        inner_options.check_variadic()?;
+
                         let command_config = command_config?;
+                        inner_options.check_relations(&command_config.exclusive)?;
                         let variant_ident = variant.ident;
                         let variant_full_name = quote! { #name::#variant_ident };
 
+                        let fields_pattern = inner_options.make_fields_pattern();
+                        let execute_fragment = inner_options.make_execute_fragment();
+
                         if command_config.for_self.unwrap_or(false) {
+                            to_fragments_match_arms.push(quote! {
+                                #variant_full_name #fields_pattern => ::std::vec![#execute_fragment]
+                            });
                             self_discovered.push(variant_full_name);
                             options = inner_options;
+                            self_permissions = command_config.permissions();
+                            self_cooldown = command_config.cooldown()?;
+                            self_confirm = command_config.confirm.clone();
+                            exclusive = command_config.exclusive;
                         } else {
                             let command_name = command_config.rename_or(&variant_ident);
                             let command_description = join_doc_string(&variant.attrs);
 
-                            let inner_options_kal: Vec<_> =
-                                inner_options.iter().map(|opt| opt.kal_option()).collect();
+                            let inner_options_kal = inner_options.make_options_spec();
+                            let inner_permissions_kal = command_config.permissions().spec();
+                            let inner_cooldown_kal =
+                                CommandCooldown::spec(&command_config.cooldown()?);
+                            let inner_confirm_kal = option_spec(&command_config.confirm);
+                            let inner_exclusive_kal =
+                                inner_options.make_exclusive_spec(&command_config.exclusive);
                             subcommands.push(quote! {
                                 ::kal::CommandSpec {
                                     name: #command_name,
                                     description: #command_description,
-                                    options: ::std::vec![#(#inner_options_kal),*],
+                                    aliases: ::std::vec::Vec::new(),
+                                    options: #inner_options_kal,
+                                    exclusive: #inner_exclusive_kal,
+                                    permissions: #inner_permissions_kal,
+                                    cooldown: #inner_cooldown_kal,
+                                    confirm: #inner_confirm_kal,
+                                    self_execution: ::std::default::Default::default(),
                                     subcommands: ::std::vec::Vec::new(),
                                 }
                             });
 
-                            let inner_options_execute_work =
-                                inner_options.make_execute_work(variant_full_name);
+                            let inner_options_execute_work = inner_options
+                                .make_execute_work(&variant_full_name, &command_config.exclusive);
                             subcommands_named_fields_match_arms.push(quote! {
                                 #command_name => #inner_options_execute_work
                             });
+                            to_fragments_match_arms.push(quote! {
+                                #variant_full_name #fields_pattern => ::std::vec![
+                                    ::kal::CommandFragment::Select(#command_name.to_string()),
+                                    #execute_fragment,
+                                ]
+                            });
                         }
                     }
                     Fields::Unnamed(fields) => {
@@ -127,20 +153,48 @@ pub fn actual_derive_command(derive_input: DeriveInput) -> error::Result<TokenSt
                         subcommands.push(quote! {
                             <#ty as ::kal::Command>::spec()
                         });
+                        to_fragments_match_arms.push(quote! {
+                            #name::#variant_name(inner) => {
+                                let mut fragments = ::std::vec![
+                                    ::kal::CommandFragment::Select(<#ty as ::kal::Command>::NAME.to_string())
+                                ];
+                                fragments.extend(<#ty as ::kal::ToFragments>::to_fragments(inner));
+                                fragments
+                            }
+                        });
                     }
                     Fields::Unit => {
                         let command_config = command_config?;
 
-                        let command_name = command_config.rename_or(&variant.ident);
+                        let variant_ident = variant.ident;
+                        let command_name = command_config.rename_or(&variant_ident);
                         let command_description = join_doc_string(&variant.attrs);
+                        let permissions_kal = command_config.permissions().spec();
+                        let cooldown_kal = CommandCooldown::spec(&command_config.cooldown()?);
+                        let confirm_kal = option_spec(&command_config.confirm);
                         subcommands.push(quote! {
                             ::kal::CommandSpec {
                                 name: #command_name,
                                 description: #command_description,
+                                aliases: ::std::vec::Vec::new(),
                                 options: ::std::vec::Vec::new(),
+                                exclusive: ::std::vec::Vec::new(),
+                                permissions: #permissions_kal,
+                                cooldown: #cooldown_kal,
+                                confirm: #confirm_kal,
+                                self_execution: ::std::default::Default::default(),
                                 subcommands: ::std::vec::Vec::new(),
                             }
-                        })
+                        });
+                        subcommands_named_fields_match_arms.push(quote! {
+                            #command_name => ::std::result::Result::Ok(#name::#variant_ident)
+                        });
+                        to_fragments_match_arms.push(quote! {
+                            #name::#variant_ident => ::std::vec![
+                                ::kal::CommandFragment::Select(#command_name.to_string()),
+                                ::kal::CommandFragment::Execute(::std::vec::Vec::new()),
+                            ]
+                        });
                     }
                 };
             }
@@ -160,10 +214,45 @@ pub fn actual_derive_command(derive_input: DeriveInput) -> error::Result<TokenSt
         ));
     }
 
-    let options_kal: Vec<_> = options.iter().map(|opt| opt.kal_option()).collect();
+    options.check_relations(&exclusive)?;
+
+    let options_kal = options.make_options_spec();
+    let exclusive_kal = options.make_exclusive_spec(&exclusive);
+    let permissions_kal = permissions.spec();
+    let self_permissions_kal = self_permissions.spec();
+    let cooldown_kal = CommandCooldown::spec(&cooldown);
+    let self_cooldown_kal = CommandCooldown::spec(&self_cooldown);
+    let confirm_kal = option_spec(&confirm);
+    let self_confirm_kal = option_spec(&self_confirm);
+
+    let to_fragments_body = if to_fragments_match_arms.is_empty() {
+        let fields_pattern = options.make_fields_pattern();
+        let execute_fragment = options.make_execute_fragment();
+        quote! {
+            let #name #fields_pattern = self;
+            ::std::vec![#execute_fragment]
+        }
+    } else {
+        quote! {
+            match self {
+                #(#to_fragments_match_arms),*
+            }
+        }
+    };
+
+    // Rendering needs every field type to implement `ToArgumentValue`, so it is opt-in.
+    let to_fragments_impl = to_fragments.then(|| {
+        quote! {
+            impl ::kal::ToFragments for #name {
+                fn to_fragments(&self) -> ::std::vec::Vec<::kal::CommandFragment> {
+                    #to_fragments_body
+                }
+            }
+        }
+    });
 
     let self_arm = if let Some(self_token) = self_discovered.first() {
-        let work = options.make_execute_work(self_token);
+        let work = options.make_execute_work(self_token, &exclusive);
         quote! {
             [::kal::CommandFragment::Execute(arguments), ..] => #work
         }
@@ -182,10 +271,25 @@ pub fn actual_derive_command(derive_input: DeriveInput) -> error::Result<TokenSt
                 ::kal::CommandSpec {
                     name: #root_command_name,
                     description: #root_command_description,
-                    options: ::std::vec![#(#options_kal),*],
+                    aliases: ::std::vec::Vec::new(),
+                    options: #options_kal,
+                    exclusive: #exclusive_kal,
+                    permissions: #permissions_kal,
+                    cooldown: #cooldown_kal,
+                    confirm: #confirm_kal,
+                    self_execution: ::kal::CommandSelfExecution {
+                        permissions: #self_permissions_kal,
+                        cooldown: #self_cooldown_kal,
+                        confirm: #self_confirm_kal,
+                    },
                     subcommands: ::std::vec![#(#subcommands),*],
                 }
             }
+            fn spec_ref() -> &'static ::kal::CommandSpec {
+                static SPEC: ::std::sync::OnceLock<::kal::CommandSpec> = ::std::sync::OnceLock::new();
+                SPEC.get_or_init(<Self as ::kal::Command>::spec)
+            }
+
             fn parse(fragments: &[::kal::CommandFragment]) -> ::std::result::Result<Self, ::kal::CommandParseError> {
                 match fragments {
                     [
@@ -195,14 +299,14 @@ pub fn actual_derive_command(derive_input: DeriveInput) -> error::Result<TokenSt
                     ] => {
                         let rest = ::std::slice::from_ref(execute);
                         match name.as_str() {
-                            #(#subcommands_named_fields_match_arms),*
-                            #(#subcommand_match_arms),*
+                            #(#subcommands_named_fields_match_arms,)*
+                            #(#subcommand_match_arms,)*
                             _ => ::std::result::Result::Err(::kal::CommandParseError::UnknownCommand(name)),
                         }
                     }
                     [::kal::CommandFragment::Select(name), rest @ ..] => {
                         match name.as_str() {
-                            #(#subcommand_match_arms),*
+                            #(#subcommand_match_arms,)*
                             _ => ::std::result::Result::Err(::kal::CommandParseError::UnknownCommand(name)),
                         }
                     }
@@ -211,6 +315,15 @@ pub fn actual_derive_command(derive_input: DeriveInput) -> error::Result<TokenSt
                 }
             }
         }
+
+        #to_fragments_impl
     }
     .into())
 }
+
+fn option_spec(value: &Option<String>) -> quote::__private::TokenStream {
+    match value {
+        Some(value) => quote! { ::std::option::Option::Some(#value) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
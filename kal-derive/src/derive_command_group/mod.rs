@@ -0,0 +1,204 @@
+use darling::{FromDeriveInput, FromVariant};
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Fields};
+
+use crate::common::{
+    codegen::command_cooldown_codegen::CommandCooldown,
+    config::command_group_config::{CommandGroupConfig, GroupMemberConfig},
+    doc_string::join_doc_string,
+    error::{self, Error},
+};
+
+pub fn actual_derive_command_group(derive_input: DeriveInput) -> error::Result<TokenStream> {
+    let root_config = CommandGroupConfig::from_derive_input(&derive_input)?;
+    let root_command_name = root_config.rename_or(&derive_input.ident);
+    let root_command_description = root_config
+        .description
+        .clone()
+        .unwrap_or_else(|| join_doc_string(&derive_input.attrs));
+
+    let name = derive_input.ident;
+
+    let data = match derive_input.data {
+        syn::Data::Enum(data) => data,
+        syn::Data::Struct(data) => {
+            return Err(Error::new(
+                data.struct_token,
+                "Cannot derive CommandGroup for struct",
+            ))
+        }
+        syn::Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token,
+                "Cannot derive CommandGroup for union",
+            ))
+        }
+    };
+
+    let mut subcommands = Vec::new();
+    let mut parse_match_arms = Vec::new();
+    let mut to_fragments_match_arms = Vec::new();
+    let mut hint_inserts = Vec::new();
+
+    for variant in data.variants {
+        let member_config = GroupMemberConfig::from_variant(&variant)?;
+        let fields = match variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => fields,
+            fields => {
+                return Err(Error::new(
+                    fields,
+                    "CommandGroup variant must have one unnamed field",
+                ))
+            }
+        };
+        let variant_ident = variant.ident;
+        let ty = &fields.unnamed[0].ty;
+
+        let member_name = match &member_config.rename {
+            Some(rename) => quote! { #rename },
+            None => quote! { <#ty as ::kal::Command>::NAME },
+        };
+        let aliases = &member_config.alias;
+        let description = member_config
+            .description
+            .clone()
+            .unwrap_or_else(|| join_doc_string(&variant.attrs));
+
+        let rename_spec = member_config
+            .rename
+            .as_ref()
+            .map(|rename| quote! { spec.name = #rename; });
+        let describe_spec =
+            (!description.is_empty()).then(|| quote! { spec.description = #description; });
+        let alias_spec =
+            (!aliases.is_empty()).then(|| quote! { spec.aliases.extend([#(#aliases),*]); });
+        let member_permissions = member_config.permissions();
+        let permit_spec = (!member_permissions.is_empty()).then(|| {
+            let required = &member_permissions.required;
+            let owner_only = member_permissions
+                .owner_only
+                .then(|| quote! { spec.permissions.owner_only = true; });
+            quote! {
+                spec.permissions.required.extend([#(#required),*]);
+                #owner_only
+            }
+        });
+        let cool_spec = member_config.cooldown()?.map(|cooldown| {
+            let cooldown = CommandCooldown::spec(&Some(cooldown));
+            quote! { spec.cooldown = #cooldown; }
+        });
+        let confirm_spec = member_config
+            .confirm
+            .as_ref()
+            .map(|confirm| quote! { spec.confirm = ::std::option::Option::Some(#confirm); });
+        subcommands.push(quote! {
+            {
+                let mut spec = <#ty as ::kal::Command>::spec();
+                #rename_spec
+                #describe_spec
+                #alias_spec
+                #permit_spec
+                #cool_spec
+                #confirm_spec
+                spec
+            }
+        });
+        parse_match_arms.push(quote! {
+            #member_name #(| #aliases)* => <#ty as ::kal::Command>::parse(rest).map(#name::#variant_ident)
+        });
+        to_fragments_match_arms.push(quote! {
+            #name::#variant_ident(inner) => {
+                let mut fragments = ::std::vec![
+                    ::kal::CommandFragment::Select(#member_name.to_string())
+                ];
+                fragments.extend(<#ty as ::kal::ToFragments>::to_fragments(inner));
+                fragments
+            }
+        });
+
+        let member_hint = if member_config.hint_provider.unwrap_or(false) {
+            quote! { <#ty as ::kal::lex::TransformHintProvider>::hint() }
+        } else {
+            quote! { ::kal::lex::TransformHint::from(<#ty as ::kal::Command>::spec_ref()) }
+        };
+        hint_inserts.push(quote! {
+            let hint = #member_hint;
+            #(map.insert(#aliases, hint.clone());)*
+            map.insert(#member_name, hint);
+        });
+    }
+
+    let hint_impl = if cfg!(feature = "lex") {
+        Some(quote! {
+            impl ::kal::lex::TransformHintProvider for #name {
+                fn hint() -> ::kal::lex::TransformHint {
+                    let mut map = ::std::collections::HashMap::new();
+                    #({ #hint_inserts })*
+                    ::kal::lex::TransformHint::Select(map)
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    // Every member should implement `ToFragments` as well, so it is opt-in.
+    let to_fragments_impl = root_config.to_fragments.unwrap_or(false).then(|| {
+        quote! {
+            impl ::kal::ToFragments for #name {
+                fn to_fragments(&self) -> ::std::vec::Vec<::kal::CommandFragment> {
+                    match self {
+                        #(#to_fragments_match_arms),*
+                    }
+                }
+            }
+        }
+    });
+
+    let root_permissions = root_config.permissions().spec();
+
+    Ok(quote! {
+        impl ::kal::Command for #name {
+            const NAME: &'static str = #root_command_name;
+
+            fn spec() -> ::kal::CommandSpec {
+                ::kal::CommandSpec {
+                    name: #root_command_name,
+                    description: #root_command_description,
+                    aliases: ::std::vec::Vec::new(),
+                    options: ::std::vec::Vec::new(),
+                    exclusive: ::std::vec::Vec::new(),
+                    permissions: #root_permissions,
+                    cooldown: ::std::option::Option::None,
+                    confirm: ::std::option::Option::None,
+                    self_execution: ::std::default::Default::default(),
+                    subcommands: ::std::vec![#(#subcommands),*],
+                }
+            }
+
+            fn spec_ref() -> &'static ::kal::CommandSpec {
+                static SPEC: ::std::sync::OnceLock<::kal::CommandSpec> = ::std::sync::OnceLock::new();
+                SPEC.get_or_init(<Self as ::kal::Command>::spec)
+            }
+
+            fn parse(fragments: &[::kal::CommandFragment]) -> ::std::result::Result<Self, ::kal::CommandParseError> {
+                match fragments {
+                    [::kal::CommandFragment::Select(name), rest @ ..] => {
+                        match name.as_str() {
+                            #(#parse_match_arms,)*
+                            _ => ::std::result::Result::Err(::kal::CommandParseError::UnknownCommand(name)),
+                        }
+                    }
+                    [::kal::CommandFragment::Execute(_), ..] => ::std::result::Result::Err(::kal::CommandParseError::ExecuteTooEarly),
+                    [] => ::std::result::Result::Err(::kal::CommandParseError::IncompleteCommand),
+                }
+            }
+        }
+
+        #to_fragments_impl
+
+        #hint_impl
+    }
+    .into())
+}
@@ -1,12 +1,11 @@
-use darling::{FromField, FromVariant};
+use darling::FromVariant;
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{DeriveInput, Fields};
 
 use crate::common::{
     codegen::command_option_codegen::{CommandOption, CommandOptionsExt},
-    config::{argument_config::ArgumentConfig, command_config::CommandConfig},
-    doc_string::join_doc_string,
+    config::command_config::CommandConfig,
     error::{self, Error},
 };
 
@@ -24,23 +23,7 @@ pub fn actual_derive_transform_hint(derive_input: DeriveInput) -> error::Result<
                 #name
             });
             for field in data.fields {
-                let argument_config = ArgumentConfig::from_field(&field)?;
-                let field_ident = field
-                    .ident
-                    .clone()
-                    .ok_or_else(|| Error::new(&field, "enum variant field must have a name"))?;
-                let argument_name = argument_config.rename_or(&field_ident);
-                let argument_description = join_doc_string(&field.attrs);
-
-                options.push(CommandOption {
-                    ident: field_ident,
-                    name: argument_name,
-                    position: options.len(),
-                    description: argument_description,
-                    ty: field.ty,
-                    take_rest: argument_config.take_rest,
-                    default: argument_config.default,
-                });
+                options.push(CommandOption::from_field(field, options.next_position())?);
             }
         }
         syn::Data::Enum(data) => {
@@ -51,21 +34,10 @@ pub fn actual_derive_transform_hint(derive_input: DeriveInput) -> error::Result<
                     Fields::Named(fields) => {
                         let mut inner_options = Vec::new();
                         for field in fields.named {
-                            let argument_config = ArgumentConfig::from_field(&field)?;
-                            let ident = field.ident.clone().ok_or_else(|| {
-                                Error::new(&field, "enum variant field must have a name")
-                            })?;
-                            let argument_name = argument_config.rename_or(&ident);
-                            let argument_description = join_doc_string(&field.attrs);
-                            inner_options.push(CommandOption {
-                                ident,
-                                name: argument_name,
-                                position: inner_options.len(),
-                                description: argument_description,
-                                ty: field.ty,
-                                take_rest: argument_config.take_rest,
-                                default: argument_config.default,
-                            });
+                            inner_options.push(CommandOption::from_field(
+                                field,
+                                inner_options.next_position(),
+                            )?);
                         }
 
                         let command_config = command_config?;
@@ -102,8 +74,10 @@ pub fn actual_derive_transform_hint(derive_input: DeriveInput) -> error::Result<
                         let command_config = command_config?;
 
                         let command_name = command_config.rename_or(&variant.ident);
-                        subcommands
-                            .push((quote! { #command_name }, quote! { ::std::vec::Vec::new() }));
+                        subcommands.push((
+                            quote! { #command_name },
+                            quote! { ::kal::lex::TransformHint::Execute(::std::vec::Vec::new()) },
+                        ));
                     }
                 };
             }
@@ -1,10 +1,14 @@
+use derive_arguments::actual_derive_arguments;
 use derive_command::actual_derive_command;
+use derive_command_group::actual_derive_command_group;
 use derive_transform_hint_provider::actual_derive_transform_hint;
 use proc_macro::TokenStream;
 use syn::{parse_macro_input, DeriveInput};
 
 mod common;
+mod derive_arguments;
 mod derive_command;
+mod derive_command_group;
 mod derive_transform_hint_provider;
 
 /// Derive Command trait from kal for a struct or an enum.
@@ -18,6 +22,29 @@ pub fn derive_command(item: TokenStream) -> TokenStream {
     }
 }
 
+/// Derive Arguments trait from kal for a struct flattened into commands.
+#[proc_macro_derive(Arguments, attributes(argument))]
+pub fn derive_arguments(item: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(item as DeriveInput);
+
+    match actual_derive_arguments(derive_input) {
+        Ok(stream) => stream,
+        Err(error) => TokenStream::from(error),
+    }
+}
+
+/// Derive Command trait from kal for an enum grouping commands, one command per variant.
+/// TransformHintProvider trait is derived together with `"lex"` feature flag.
+#[proc_macro_derive(CommandGroup, attributes(command))]
+pub fn derive_command_group(item: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(item as DeriveInput);
+
+    match actual_derive_command_group(derive_input) {
+        Ok(stream) => stream,
+        Err(error) => TokenStream::from(error),
+    }
+}
+
 #[cfg(feature = "lex")]
 /// Derive TransformHintProvider trait from kal for a struct or an enum.
 #[proc_macro_derive(TransformHintProvider, attributes(command, argument))]
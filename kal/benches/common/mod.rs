@@ -0,0 +1,53 @@
+//! The messages and hint shared by the transform bench and the allocation test of testsuite.
+
+use std::collections::HashMap;
+
+use kal::lex::{TransformHint, TransformHintPart};
+
+pub const MESSAGES: [&str; 4] = [
+    "/ping",
+    "/remind at 5 \"buy milk\"",
+    "/remind note it is a great day",
+    "/admin ban user=someone days=7",
+];
+
+/// The hint for messages, with `filler` more commands to make the tree wider.
+pub fn hint(filler: usize) -> TransformHint {
+    let mut commands: HashMap<_, _> = (0..filler)
+        .map(|i| {
+            let name: &'static str = Box::leak(format!("command{}", i).into_boxed_str());
+            (
+                name,
+                TransformHint::Execute(vec![("value", TransformHintPart::String)]),
+            )
+        })
+        .collect();
+    commands.insert("ping", TransformHint::Execute(vec![]));
+    commands.insert(
+        "remind",
+        TransformHint::Select(HashMap::from_iter([
+            (
+                "at",
+                TransformHint::Execute(vec![
+                    ("minutes", TransformHintPart::Integer),
+                    ("message", TransformHintPart::String),
+                ]),
+            ),
+            (
+                "note",
+                TransformHint::Execute(vec![("text", TransformHintPart::StringGreedy)]),
+            ),
+        ])),
+    );
+    commands.insert(
+        "admin",
+        TransformHint::Select(HashMap::from_iter([(
+            "ban",
+            TransformHint::Execute(vec![
+                ("user", TransformHintPart::String),
+                ("days", TransformHintPart::Integer),
+            ]),
+        )])),
+    );
+    TransformHint::Select(commands)
+}
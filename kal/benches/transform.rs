@@ -0,0 +1,29 @@
+mod common;
+
+use std::hint::black_box;
+
+use common::{hint, MESSAGES};
+use criterion::{criterion_group, criterion_main, Criterion};
+use kal::lex::{remove_leading, CommandLexer, TokenTransformer};
+
+fn transform(c: &mut Criterion) {
+    // As wide as a bot with a few dozen commands.
+    let transformer = TokenTransformer::command_group(|s| remove_leading("/", s), hint(32));
+    let run = |message: &'static str| {
+        let fragments: Result<Vec<_>, _> = transformer
+            .transform(CommandLexer::new(black_box(message)))
+            .collect();
+        black_box(fragments)
+    };
+
+    c.bench_function("transform", |b| {
+        b.iter(|| {
+            for message in MESSAGES {
+                let _ = run(message);
+            }
+        })
+    });
+}
+
+criterion_group!(benches, transform);
+criterion_main!(benches);
@@ -0,0 +1,19 @@
+use crate::{CommandArgument, CommandArgumentValue, CommandOption, CommandParseError};
+
+/// A group of arguments shared by commands, spliced into a command with `#[argument(flatten)]`.
+pub trait Arguments: Sized {
+    /// The number of options the group takes.
+    const COUNT: usize;
+
+    /// The options the group takes, positioned from `offset`.
+    fn options(offset: usize) -> Vec<CommandOption>;
+
+    /// Try take the group from arguments positioned from `offset`.
+    fn from_arguments(
+        arguments: &[CommandArgument],
+        offset: usize,
+    ) -> Result<Self, CommandParseError<'static>>;
+
+    /// The name and value of each argument in position order.
+    fn to_argument_values(&self) -> Vec<(&'static str, Option<CommandArgumentValue>)>;
+}
@@ -1,3 +1,9 @@
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
 use crate::{CommandFragment, CommandParseError, CommandSpec};
 
 /// The command
@@ -8,6 +14,31 @@ pub trait Command: Sized {
     /// The spec of command for command registration.
     fn spec() -> CommandSpec;
 
+    /// The spec of command built once and cached for the rest of the program.
+    ///
+    /// The default leaks the spec once per type into a global map, which the derives replace with a static of their own.
+    fn spec_ref() -> &'static CommandSpec
+    where
+        Self: 'static,
+    {
+        static SPECS: OnceLock<Mutex<HashMap<TypeId, &'static CommandSpec>>> = OnceLock::new();
+        let mut specs = SPECS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        specs
+            .entry(TypeId::of::<Self>())
+            .or_insert_with(|| Box::leak(Box::new(Self::spec())))
+    }
+
     /// Try parse command from [`CommandFragment`] sequence.
-    fn parse(fragments: &[CommandFragment]) -> Result<Self, CommandParseError>;
+    fn parse(fragments: &[CommandFragment]) -> Result<Self, CommandParseError<'_>>;
+}
+
+/// The command which can be turned back into [`CommandFragment`] sequence.
+/// The derive implements it with `#[command(to_fragments)]`,
+/// as every field type should implement [`ToArgumentValue`](`crate::ToArgumentValue`) then.
+pub trait ToFragments: Command {
+    /// Make [`CommandFragment`] sequence which [`Command::parse`] parses back to the same value.
+    fn to_fragments(&self) -> Vec<CommandFragment>;
 }
@@ -1,7 +1,8 @@
 use core::fmt;
 
 /// Command argument
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CommandArgument {
     /// Named argument
     Named(String, CommandArgumentValue),
@@ -12,6 +13,7 @@ pub enum CommandArgument {
 
 /// The actual argument value
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CommandArgumentValue {
     /// The string type.
     String(String),
@@ -1,13 +1,16 @@
 pub use command_argument::{CommandArgument, CommandArgumentValue, CommandArgumentValueType};
+pub use to_argument_value::ToArgumentValue;
 pub use try_from_argument_value::{TryFromArgumentValue, TryFromArgumentValueError};
 pub use wrapper::{CommaSeparated, SpaceSeparated};
 
 mod command_argument;
+mod to_argument_value;
 mod try_from_argument_value;
 mod wrapper;
 
 /// The fragment of user command
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CommandFragment {
     /// Select subcommand
     Select(String),
@@ -0,0 +1,7 @@
+use crate::{CommandArgumentValue, ToArgumentValue};
+
+impl ToArgumentValue for i64 {
+    fn to_argument_value(&self) -> Option<CommandArgumentValue> {
+        Some(CommandArgumentValue::I64(*self))
+    }
+}
@@ -0,0 +1,5 @@
+mod f64;
+mod i64;
+mod option;
+mod string;
+mod vec;
@@ -0,0 +1,11 @@
+use crate::{CommandArgumentValue, ToArgumentValue};
+
+impl<T: ToArgumentValue> ToArgumentValue for Option<T> {
+    fn to_argument_value(&self) -> Option<CommandArgumentValue> {
+        self.as_ref().and_then(T::to_argument_value)
+    }
+
+    fn to_argument_values(&self) -> Vec<CommandArgumentValue> {
+        self.as_ref().map(T::to_argument_values).unwrap_or_default()
+    }
+}
@@ -0,0 +1,7 @@
+use crate::{CommandArgumentValue, ToArgumentValue};
+
+impl ToArgumentValue for String {
+    fn to_argument_value(&self) -> Option<CommandArgumentValue> {
+        Some(CommandArgumentValue::String(self.clone()))
+    }
+}
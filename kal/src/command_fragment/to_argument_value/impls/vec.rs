@@ -0,0 +1,38 @@
+use crate::{CommaSeparated, CommandArgumentValue, SpaceSeparated, ToArgumentValue};
+
+macro_rules! join_vec {
+    ($value:expr, $separator:expr) => {
+        Some(CommandArgumentValue::String(
+            $value
+                .iter()
+                .filter_map(T::to_argument_value)
+                .map(|value| match value {
+                    CommandArgumentValue::String(s) => s,
+                    CommandArgumentValue::I64(i) => i.to_string(),
+                    CommandArgumentValue::F64(f) => f.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join($separator),
+        ))
+    };
+}
+
+impl<T: ToArgumentValue> ToArgumentValue for SpaceSeparated<T> {
+    fn to_argument_value(&self) -> Option<CommandArgumentValue> {
+        join_vec!(self, " ")
+    }
+
+    fn to_argument_values(&self) -> Vec<CommandArgumentValue> {
+        self.iter().flat_map(T::to_argument_values).collect()
+    }
+}
+
+impl<T: ToArgumentValue> ToArgumentValue for CommaSeparated<T> {
+    fn to_argument_value(&self) -> Option<CommandArgumentValue> {
+        join_vec!(self, ",")
+    }
+
+    fn to_argument_values(&self) -> Vec<CommandArgumentValue> {
+        self.iter().flat_map(T::to_argument_values).collect()
+    }
+}
@@ -0,0 +1,14 @@
+use crate::CommandArgumentValue;
+
+mod impls;
+
+/// The trait to convert a value back to [`CommandArgumentValue`], the inverse of [`TryFromArgumentValue`](`crate::TryFromArgumentValue`).
+pub trait ToArgumentValue {
+    /// Convert itself to [`CommandArgumentValue`], or `None` if the argument should be omitted.
+    fn to_argument_value(&self) -> Option<CommandArgumentValue>;
+
+    /// Convert each value it holds to [`CommandArgumentValue`], to validate them one by one.
+    fn to_argument_values(&self) -> Vec<CommandArgumentValue> {
+        self.to_argument_value().into_iter().collect()
+    }
+}
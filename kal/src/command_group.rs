@@ -2,6 +2,9 @@
 /// where its every variant have only one field implements [`Commnad`](`crate::Command`).
 /// The enum will provide a parse function matching each variant's name directly.
 ///
+/// It is kept for compatibility and works without `"derive"` feature flag,
+/// prefer `#[derive(CommandGroup)]` which takes `#[command]` attributes as well.
+///
 /// Example:
 /// ```rust
 /// # use kal::{Command, command_group, CommandFragment, CommandParseError};
@@ -25,14 +28,57 @@
 /// assert_eq!(Ok(Root::A(A)), Root::parse(&[CommandFragment::Select("a".to_string()), CommandFragment::Execute(vec![])]));
 /// assert_eq!(Ok(Root::B(B)), Root::parse(&[CommandFragment::Select("b".to_string()), CommandFragment::Execute(vec![])]));
 /// assert_eq!(Err(CommandParseError::UnknownCommand(&"c".to_string())), Root::parse(&[CommandFragment::Select("c".to_string()), CommandFragment::Execute(vec![])]));
+/// ```
+///
+/// With `#[command(to_fragments)]` put first, the enum also implements [`ToFragments`](`crate::ToFragments`),
+/// which every member should implement as well.
+///
+/// With `"lex"` feature flag, the enum also implements [`TransformHintProvider`](`crate::lex::TransformHintProvider`).
+/// See [`lex`](`crate::lex`) module for details.
 #[macro_export]
 macro_rules! command_group {
+    (
+        #[command(to_fragments)]
+        $(#[$attrs:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$member_attr:ident])?
+                $variant:ident($path:path)
+            ),*
+            $(,)?
+        }
+    ) => {
+        ::kal::command_group! {
+            $(#[$attrs])*
+            $vis enum $name {
+                $($(#[$member_attr])? $variant($path)),*
+            }
+        }
+
+        impl ::kal::ToFragments for $name {
+            fn to_fragments(&self) -> ::std::vec::Vec<::kal::CommandFragment> {
+                match self {
+                    $(
+                        $name::$variant(inner) => {
+                            let mut fragments = ::std::vec![
+                                ::kal::CommandFragment::Select(<$path as ::kal::Command>::NAME.to_string())
+                            ];
+                            fragments.extend(<$path as ::kal::ToFragments>::to_fragments(inner));
+                            fragments
+                        }
+                    )*
+                }
+            }
+        }
+    };
     (
         $(#[$attrs:meta])*
         $vis:vis enum $name:ident {
             $(
+                $(#[$member_attr:ident])?
                 $variant:ident($path:path)
             ),*
+            $(,)?
         }
     ) => {
         $(#[$attrs])*
@@ -57,15 +103,22 @@ macro_rules! command_group {
                 ::kal::CommandSpec {
                     name: Self::NAME,
                     description: "",
+                    aliases: ::std::vec::Vec::new(),
                     options: ::std::vec::Vec::new(),
-                    subcommands: ::std::vec![
-                        $(
-                            <$path as ::kal::Command>::spec(),
-                        )*
-                    ],
+                    exclusive: ::std::vec::Vec::new(),
+                    permissions: ::std::default::Default::default(),
+                    cooldown: ::std::option::Option::None,
+                    confirm: ::std::option::Option::None,
+                    self_execution: ::std::default::Default::default(),
+                    subcommands: Self::children_specs(),
                 }
             }
 
+            fn spec_ref() -> &'static ::kal::CommandSpec {
+                static SPEC: ::std::sync::OnceLock<::kal::CommandSpec> = ::std::sync::OnceLock::new();
+                SPEC.get_or_init(<Self as ::kal::Command>::spec)
+            }
+
             fn parse(fragments: &[::kal::CommandFragment]) -> ::std::result::Result<Self, ::kal::CommandParseError> {
                 match fragments {
                     [::kal::CommandFragment::Select(name), rest @ ..] => {
@@ -82,5 +135,48 @@ macro_rules! command_group {
                 }
             }
         }
+
+        ::kal::__command_group_hint! {
+            $name {
+                $($variant($path $(, $member_attr)?)),*
+            }
+        }
     }
 }
+
+#[cfg(feature = "lex")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __command_group_hint {
+    (
+        $name:ident {
+            $($variant:ident($path:path $(, $member_attr:ident)?)),*
+        }
+    ) => {
+        impl ::kal::lex::TransformHintProvider for $name {
+            fn hint() -> ::kal::lex::TransformHint {
+                ::kal::lex::TransformHint::Select(::std::collections::HashMap::from_iter([
+                    $(
+                        (
+                            <$path as ::kal::Command>::NAME,
+                            ::kal::__command_group_hint!(@member $path $(, $member_attr)?),
+                        )
+                    ),*
+                ]))
+            }
+        }
+    };
+    (@member $path:path) => {
+        ::kal::lex::TransformHint::from(<$path as ::kal::Command>::spec_ref())
+    };
+    (@member $path:path, hint_provider) => {
+        <$path as ::kal::lex::TransformHintProvider>::hint()
+    };
+}
+
+#[cfg(not(feature = "lex"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __command_group_hint {
+    ($($tt:tt)*) => {};
+}
@@ -1,7 +1,17 @@
-use crate::{CommaSeparated, SpaceSeparated};
+use std::cmp::Ordering;
+#[cfg(feature = "regex")]
+use std::{borrow::Cow, sync::OnceLock};
+
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+use crate::{
+    CommaSeparated, CommandArgumentValue, CommandCooldown, CommandFragment, CommandPermissions,
+    CompletionProvider, SpaceSeparated,
+};
 
 /// The specification of coomand
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct CommandSpec {
     /// The name of command
     pub name: &'static str,
@@ -9,15 +19,73 @@ pub struct CommandSpec {
     /// The description of command
     pub description: &'static str,
 
+    /// The other names command can be selected with
+    pub aliases: Vec<&'static str>,
+
     /// The options command can take
     pub options: Vec<CommandOption>,
 
+    /// The groups of options which cannot be given together
+    pub exclusive: Vec<Vec<&'static str>>,
+
+    /// The permissions required to run command
+    pub permissions: CommandPermissions,
+
+    /// The cooldown between each run of command
+    pub cooldown: Option<CommandCooldown>,
+
+    /// The message asking confirmation before running command, which its subcommands don't ask
+    pub confirm: Option<&'static str>,
+
+    /// The requirements of executing command itself, not shared with its subcommands
+    pub self_execution: CommandSelfExecution,
+
     /// The subcommands command have
     pub subcommands: Vec<CommandSpec>,
 }
 
+impl CommandSpec {
+    /// Find the subcommand selected by its name or aliases.
+    pub fn subcommand(&self, name: &str) -> Option<&CommandSpec> {
+        self.subcommands
+            .iter()
+            .find(|subcommand| subcommand.name == name || subcommand.aliases.contains(&name))
+    }
+
+    /// The specs selected by the leading [`CommandFragment::Select`] sequence,
+    /// starting with the spec itself.
+    pub fn select_path(&self, fragments: &[CommandFragment]) -> Vec<&CommandSpec> {
+        let mut path = vec![self];
+        for fragment in fragments {
+            let CommandFragment::Select(name) = fragment else {
+                break;
+            };
+            let Some(subcommand) = path[path.len() - 1].subcommand(name) else {
+                break;
+            };
+            path.push(subcommand);
+        }
+        path
+    }
+}
+
+/// The requirements of executing a command itself, which its subcommands don't share
+///
+/// The derive sets them from the attributes of `#[command(self)]` variant.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommandSelfExecution {
+    /// The permissions required to execute command itself
+    pub permissions: CommandPermissions,
+
+    /// The cooldown between each execution of command itself
+    pub cooldown: Option<CommandCooldown>,
+
+    /// The message asking confirmation before executing command itself
+    pub confirm: Option<&'static str>,
+}
+
 /// The option command cane take
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct CommandOption {
     /// The name of option when it is treated as named argument
     pub name: &'static str,
@@ -30,6 +98,176 @@ pub struct CommandOption {
 
     /// The kind of value option can take
     pub value: CommandOptionValueKind,
+
+    /// Whether the option takes the rest of the command as is
+    pub take_rest: bool,
+
+    /// Whether the option takes every remaining positioned argument
+    pub variadic: bool,
+
+    /// The constraints the option value should satisfy
+    pub validation: CommandOptionValidation,
+
+    /// The options which should be given together with the option
+    pub requires: Vec<&'static str>,
+
+    /// The provider of values to complete the option with, besides [`CommandOptionValidation::choices`]
+    pub completion_provider: Option<&'static dyn CompletionProvider>,
+}
+
+/// The constraints an option value should satisfy
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommandOptionValidation {
+    /// The minimum value of numeric option
+    pub min_value: Option<f64>,
+
+    /// The maximum value of numeric option
+    pub max_value: Option<f64>,
+
+    /// The minimum length of string option in characters
+    pub min_length: Option<usize>,
+
+    /// The maximum length of string option in characters
+    pub max_length: Option<usize>,
+
+    /// The regular expression string option should match, with `"regex"` feature flag
+    #[cfg(feature = "regex")]
+    pub pattern: Option<CommandOptionPattern>,
+
+    /// The values option can only be one of, if any
+    pub choices: Vec<CommandArgumentValue>,
+}
+
+impl CommandOptionValidation {
+    /// Check the argument value, returning the reason if it is rejected.
+    pub fn check(&self, value: &CommandArgumentValue) -> Result<(), String> {
+        if !self.choices.is_empty() && !self.choices.contains(value) {
+            let choices: Vec<_> = self
+                .choices
+                .iter()
+                .map(|choice| match choice {
+                    CommandArgumentValue::String(s) => s.clone(),
+                    CommandArgumentValue::I64(i) => i.to_string(),
+                    CommandArgumentValue::F64(f) => f.to_string(),
+                })
+                .collect();
+            return Err(format!("must be one of {}", choices.join(", ")));
+        }
+        let compare = |bound: f64| match value {
+            CommandArgumentValue::I64(i) => compare_i64(*i, bound),
+            CommandArgumentValue::F64(f) => f.partial_cmp(&bound),
+            CommandArgumentValue::String(_) => None,
+        };
+        if let CommandArgumentValue::String(s) = value {
+            return self.check_str(s);
+        }
+        match (self.min_value, self.max_value) {
+            (Some(min), _) if compare(min) == Some(Ordering::Less) => {
+                Err(format!("must be at least {}", min))
+            }
+            (_, Some(max)) if compare(max) == Some(Ordering::Greater) => {
+                Err(format!("must be at most {}", max))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_str(&self, s: &str) -> Result<(), String> {
+        let length = s.chars().count();
+        match (self.min_length, self.max_length) {
+            (Some(min), _) if length < min => {
+                return Err(format!("must be at least {} characters long", min))
+            }
+            (_, Some(max)) if length > max => {
+                return Err(format!("must be at most {} characters long", max))
+            }
+            _ => {}
+        }
+        #[cfg(feature = "regex")]
+        if let Some(pattern) = &self.pattern {
+            if !pattern.is_match(s)? {
+                return Err(format!("must match pattern `{}`", pattern.as_str()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compare the integer with the bound exactly, which `i as f64` would round beyond 2^53.
+fn compare_i64(i: i64, bound: f64) -> Option<Ordering> {
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0;
+    if bound.is_nan() {
+        None
+    } else if bound >= LIMIT {
+        Some(Ordering::Less)
+    } else if bound < -LIMIT {
+        Some(Ordering::Greater)
+    } else {
+        // The truncated bound fits in i64 exactly, and only its fraction decides a tie.
+        Some(
+            i.cmp(&(bound.trunc() as i64))
+                .then_with(|| 0.0.partial_cmp(&bound.fract()).unwrap()),
+        )
+    }
+}
+
+/// The regular expression an option value should match, compiled once on its first use.
+#[cfg(feature = "regex")]
+#[derive(Clone, Debug)]
+pub struct CommandOptionPattern {
+    source: Cow<'static, str>,
+    regex: OnceLock<Regex>,
+}
+
+#[cfg(feature = "regex")]
+impl CommandOptionPattern {
+    /// Make the pattern to be compiled on its first use.
+    /// The derive checks the pattern while expanding.
+    pub const fn new(source: &'static str) -> Self {
+        CommandOptionPattern {
+            source: Cow::Borrowed(source),
+            regex: OnceLock::new(),
+        }
+    }
+
+    /// Compile the pattern right away, for the ones made at runtime.
+    pub fn compile(source: impl Into<String>) -> Result<Self, regex::Error> {
+        let source = source.into();
+        let regex = Regex::new(&source)?;
+        Ok(CommandOptionPattern {
+            source: Cow::Owned(source),
+            regex: OnceLock::from(regex),
+        })
+    }
+
+    /// The source of the pattern.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// The compiled pattern, compiling it if not yet.
+    pub fn regex(&self) -> Result<&Regex, regex::Error> {
+        if let Some(regex) = self.regex.get() {
+            return Ok(regex);
+        }
+        let regex = Regex::new(&self.source)?;
+        Ok(self.regex.get_or_init(|| regex))
+    }
+
+    /// Whether the string matches the pattern, compiling it if not yet.
+    pub fn is_match(&self, s: &str) -> Result<bool, String> {
+        let regex = self
+            .regex()
+            .map_err(|e| format!("has invalid pattern `{}`: {}", self.source, e))?;
+        Ok(regex.is_match(s))
+    }
+}
+
+#[cfg(feature = "regex")]
+impl PartialEq for CommandOptionPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
 }
 
 /// The kind of value option can take
@@ -107,6 +345,16 @@ impl CommandOptionValueTy for f64 {
     }
 }
 
+impl<T: CommandOptionValueTy> CommandOptionValueTy for Vec<T> {
+    fn spec_kind() -> CommandOptionValueKind {
+        CommandOptionValueKind::Multiple(Box::new(T::spec_kind()))
+    }
+
+    fn default() -> Option<Self> {
+        Some(Vec::new())
+    }
+}
+
 impl<T: CommandOptionValueTy> CommandOptionValueTy for SpaceSeparated<T> {
     fn spec_kind() -> CommandOptionValueKind {
         CommandOptionValueKind::Multiple(Box::new(T::spec_kind()))
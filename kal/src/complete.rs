@@ -0,0 +1,297 @@
+//! Suggest the next part of a command being typed.
+#![cfg(feature = "lex")]
+
+use std::ops::Range;
+
+use crate::{
+    lex::{
+        render::render_argument_value, CommandLexError, CommandLexer, CommandToken,
+        TokenTransformer, TransformHint,
+    },
+    CommandArgument, CommandArgumentValue, CommandFragment, CommandOption, CommandSpec,
+    CompletionContext, CompletionProvider,
+};
+
+/// A candidate to complete the command with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Completion {
+    /// The text to replace the span with
+    pub value: String,
+
+    /// The description of candidate
+    pub description: String,
+
+    /// What the candidate is
+    pub kind: CompletionKind,
+
+    /// The byte range of input the value replaces
+    pub span: Range<usize>,
+}
+
+/// What [`Completion`] suggests.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompletionKind {
+    /// A subcommand name
+    Subcommand,
+
+    /// A named argument not supplied yet, written as `name=`
+    ArgumentName,
+
+    /// A value of the argument
+    Choice,
+}
+
+/// The word being typed at the cursor.
+enum Partial<'a> {
+    /// A positioned word or a subcommand name
+    Word(&'a str),
+
+    /// The value of named argument
+    Named(&'a str, &'a str),
+}
+
+/// Suggest completions for the input typed up to the cursor, a byte offset in the input.
+/// The cursor past the end is taken as the end, and the one inside a character as the start of it.
+///
+/// The input is lexed with [`CommandLexer`] tolerating an unclosed trailing quote,
+/// and each word selects the subcommand of `spec` until the arguments start.
+/// Candidates are subcommands, argument names not supplied yet and choices of the argument,
+/// starting with the word at the cursor.
+/// The argument with [`CommandOption::completion_provider`] also takes [`CompletionProvider::complete`].
+pub fn complete(spec: &CommandSpec, input: &str, cursor: usize) -> Vec<Completion> {
+    let Some(mut pending) = analyze(spec, input, cursor) else {
+        return Vec::new();
+    };
+    if let Some((provider, context)) = pending.context() {
+        let values = provider.complete(&context);
+        pending.provide(values);
+    }
+    pending.completions
+}
+
+/// Suggest completions like [`complete`],
+/// taking [`CompletionProvider::complete_async`] for the argument with the provider.
+pub async fn complete_async(spec: &CommandSpec, input: &str, cursor: usize) -> Vec<Completion> {
+    let Some(mut pending) = analyze(spec, input, cursor) else {
+        return Vec::new();
+    };
+    if let Some((provider, context)) = pending.context() {
+        let values = provider.complete_async(&context).await;
+        pending.provide(values);
+    }
+    pending.completions
+}
+
+/// The completions waiting for the values of [`CompletionProvider`].
+struct Pending<'s, 'i> {
+    completions: Vec<Completion>,
+    span: Range<usize>,
+    path: Vec<&'s CommandSpec>,
+    arguments: Vec<CommandArgument>,
+    provided: Option<(&'s CommandOption, &'i str)>,
+}
+
+impl Pending<'_, '_> {
+    fn context(&self) -> Option<(&'static dyn CompletionProvider, CompletionContext<'_>)> {
+        let (option, prefix) = self.provided?;
+        let provider = option.completion_provider?;
+        Some((
+            provider,
+            CompletionContext {
+                path: &self.path,
+                option,
+                arguments: &self.arguments,
+                prefix,
+            },
+        ))
+    }
+
+    fn provide(&mut self, values: Vec<CommandArgumentValue>) {
+        let Some((option, _)) = self.provided else {
+            return;
+        };
+        for value in values {
+            self.completions.push(Completion {
+                value: render_argument_value(&value),
+                description: option.description.to_string(),
+                kind: CompletionKind::Choice,
+                span: self.span.clone(),
+            });
+        }
+    }
+}
+
+fn analyze<'s, 'i>(
+    spec: &'s CommandSpec,
+    input: &'i str,
+    cursor: usize,
+) -> Option<Pending<'s, 'i>> {
+    let mut cursor = cursor.min(input.len());
+    while !input.is_char_boundary(cursor) {
+        cursor -= 1;
+    }
+    let input = &input[..cursor];
+    let offset = |s: &str| s.as_ptr() as usize - input.as_ptr() as usize;
+
+    let mut tokens = Vec::new();
+    let mut partial = None;
+    for token in CommandLexer::new(input) {
+        match token {
+            Ok(token) => tokens.push(token),
+            Err(CommandLexError::UnclosedQuote(position, part)) => {
+                let word_start = input[..position]
+                    .rfind(char::is_whitespace)
+                    .map_or(0, |i| i + 1);
+                let name = input[word_start..position].strip_suffix('=');
+                partial = Some(match name {
+                    Some(name) => (word_start, position, Partial::Named(name, &part[1..])),
+                    None => (position, position, Partial::Word(&part[1..])),
+                });
+            }
+            Err(CommandLexError::NamedProhibitsWhitespace(position, part))
+                if position + part.len() == cursor && part.ends_with('=') =>
+            {
+                partial = Some((
+                    position,
+                    cursor,
+                    Partial::Named(&part[..part.len() - 1], ""),
+                ));
+            }
+            Err(_) => return None,
+        }
+    }
+    let (word_start, start, partial) = match partial {
+        Some(partial) => partial,
+        None => match tokens.last() {
+            None | Some(CommandToken::Whitespace(_)) => (cursor, cursor, Partial::Word("")),
+            Some(CommandToken::RawString(s, _)) => {
+                let s = *s;
+                tokens.pop();
+                (offset(s), offset(s), Partial::Word(s))
+            }
+            Some(CommandToken::Named(name, value)) => match value.as_ref() {
+                CommandToken::RawString(s, _) => {
+                    let (name, s) = (*name, *s);
+                    tokens.pop();
+                    (offset(name), offset(s), Partial::Named(name, s))
+                }
+                _ => return None,
+            },
+            Some(_) => return None,
+        },
+    };
+
+    let mut path = vec![spec];
+    let mut positioned = 0;
+    let mut named = Vec::new();
+    for token in &tokens {
+        match token {
+            CommandToken::Whitespace(_) => {}
+            CommandToken::RawString(s, _) if positioned == 0 && named.is_empty() => {
+                match path[path.len() - 1].subcommand(s) {
+                    Some(subcommand) => path.push(subcommand),
+                    None => positioned += 1,
+                }
+            }
+            CommandToken::Named(name, _) => named.push(*name),
+            _ => positioned += 1,
+        }
+    }
+    let root = spec;
+    let spec = path[path.len() - 1];
+
+    let span = start..cursor;
+    let mut completions = Vec::new();
+    let mut provided = None;
+    match partial {
+        Partial::Named(name, prefix) => {
+            if let Some(option) = spec.options.iter().find(|option| option.name == name) {
+                complete_choices(option, prefix, &span, &mut completions);
+                provided = Some((option, prefix));
+            }
+        }
+        Partial::Word(prefix) => {
+            if positioned == 0 && named.is_empty() {
+                for subcommand in &spec.subcommands {
+                    if subcommand.name.starts_with(prefix) {
+                        completions.push(Completion {
+                            value: subcommand.name.to_string(),
+                            description: subcommand.description.to_string(),
+                            kind: CompletionKind::Subcommand,
+                            span: span.clone(),
+                        });
+                    }
+                }
+            }
+            let supplied = |option: &CommandOption| {
+                named.contains(&option.name) || (option.position < positioned && !option.variadic)
+            };
+            for option in &spec.options {
+                if !supplied(option) && option.name.starts_with(prefix) {
+                    completions.push(Completion {
+                        value: format!("{}=", option.name),
+                        description: option.description.to_string(),
+                        kind: CompletionKind::ArgumentName,
+                        span: span.clone(),
+                    });
+                }
+            }
+            if let Some(option) = spec
+                .options
+                .iter()
+                .filter(|option| !supplied(option))
+                .min_by_key(|option| option.position)
+            {
+                complete_choices(option, prefix, &span, &mut completions);
+                provided = Some((option, prefix));
+            }
+        }
+    }
+
+    let arguments = match provided {
+        Some((option, _)) if option.completion_provider.is_some() => {
+            given_arguments(root, &input[..word_start])
+        }
+        _ => Vec::new(),
+    };
+    Some(Pending {
+        completions,
+        span,
+        path,
+        arguments,
+        provided,
+    })
+}
+
+/// Parse the arguments given before the word at the cursor, if they can be.
+fn given_arguments(spec: &CommandSpec, input: &str) -> Vec<CommandArgument> {
+    let transformer = TokenTransformer::command_args(TransformHint::from(spec));
+    let fragments: Result<Vec<_>, _> = transformer.transform(CommandLexer::new(input)).collect();
+    match fragments.ok().and_then(|mut fragments| fragments.pop()) {
+        Some(CommandFragment::Execute(arguments)) => arguments,
+        _ => Vec::new(),
+    }
+}
+
+fn complete_choices(
+    option: &CommandOption,
+    prefix: &str,
+    span: &Range<usize>,
+    completions: &mut Vec<Completion>,
+) {
+    for choice in &option.validation.choices {
+        let matches = match choice {
+            CommandArgumentValue::String(s) => s.starts_with(prefix),
+            CommandArgumentValue::I64(i) => i.to_string().starts_with(prefix),
+            CommandArgumentValue::F64(f) => f.to_string().starts_with(prefix),
+        };
+        if matches {
+            completions.push(Completion {
+                value: render_argument_value(choice),
+                description: option.description.to_string(),
+                kind: CompletionKind::Choice,
+                span: span.clone(),
+            });
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::{any::Any, fmt};
+
+use crate::{
+    dispatch::BoxFuture, CommandArgument, CommandArgumentValue, CommandOption, CommandSpec,
+};
+
+/// What [`CompletionProvider`] completes the option value for.
+#[derive(Clone, Copy, Debug)]
+pub struct CompletionContext<'a> {
+    /// The specs selected by the input, starting with the root spec
+    pub path: &'a [&'a CommandSpec],
+
+    /// The option the value is completed for
+    pub option: &'a CommandOption,
+
+    /// The arguments given before the value being typed
+    pub arguments: &'a [CommandArgument],
+
+    /// The part of value typed so far, as it is written
+    pub prefix: &'a str,
+}
+
+/// Provide the values an option can take while the command is typed,
+/// like the ones living in a database.
+///
+/// Implement [`CompletionProvider::complete`] for values known right away,
+/// or [`CompletionProvider::complete_async`] for values needing a lookup.
+/// The synchronous completion doesn't see the values provided only asynchronously.
+pub trait CompletionProvider: Any + Send + Sync {
+    /// Provide the values starting with [`CompletionContext::prefix`].
+    fn complete(&self, context: &CompletionContext<'_>) -> Vec<CommandArgumentValue> {
+        let _ = context;
+        Vec::new()
+    }
+
+    /// Provide the values asynchronously, which are the ones of [`CompletionProvider::complete`] by default.
+    fn complete_async<'a>(
+        &'a self,
+        context: &'a CompletionContext<'a>,
+    ) -> BoxFuture<'a, Vec<CommandArgumentValue>> {
+        Box::pin(async move { self.complete(context) })
+    }
+}
+
+impl fmt::Debug for dyn CompletionProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CompletionProvider({:p})", self)
+    }
+}
+
+/// Providers are the same when they are of the same type,
+/// so the specs made again for the same command are equal.
+impl PartialEq for dyn CompletionProvider {
+    fn eq(&self, other: &Self) -> bool {
+        Any::type_id(self) == Any::type_id(other)
+    }
+}
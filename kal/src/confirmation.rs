@@ -0,0 +1,263 @@
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, RandomState},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    Clock, CommandArgument, CommandArgumentValue, CommandFragment, CommandParseError, CommandSpec,
+    SystemClock, ToFragments,
+};
+
+/// The name of argument carrying the confirmation token.
+/// Commands asking confirmation should not have an option with the name.
+pub const CONFIRM_ARGUMENT: &str = "confirm";
+
+/// The command waiting for confirmation.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PendingConfirmation {
+    /// The token to confirm the command with, issued by [`ConfirmationTracker`]
+    pub token: String,
+
+    /// The message of [`CommandSpec::confirm`] with the argument values filled
+    pub message: String,
+
+    /// The [`CommandFragment`] sequence of the command confirmed with the token
+    pub confirmed: Vec<CommandFragment>,
+}
+
+/// The command parsed with confirmation.
+#[derive(Debug, PartialEq)]
+pub enum Confirmation<C> {
+    /// The command doesn't need confirmation or is confirmed.
+    Confirmed(C),
+
+    /// The command should be confirmed.
+    Pending(PendingConfirmation),
+}
+
+impl<C: ToFragments + 'static> Confirmation<C> {
+    /// Parse the command, and wait for confirmation if [`CommandSpec::confirm`]
+    /// or [`CommandSelfExecution::confirm`](crate::CommandSelfExecution::confirm) is set on the command selected.
+    /// The command is confirmed by the [`CONFIRM_ARGUMENT`] named argument with the token issued by `tracker`,
+    /// which is already in [`PendingConfirmation::confirmed`].
+    pub fn parse<'a>(
+        fragments: &'a [CommandFragment],
+        tracker: &ConfirmationTracker<impl Clock>,
+        caller: &(impl ConfirmationCaller + ?Sized),
+    ) -> Result<Self, CommandParseError<'a>> {
+        let command = C::parse(fragments)?;
+        let path = C::spec_ref().select_path(fragments);
+        match pending_confirmation(
+            &path,
+            command.to_fragments(),
+            fragments,
+            tracker,
+            &caller.confirmer_id(),
+        ) {
+            Some(pending) => Ok(Confirmation::Pending(pending)),
+            None => Ok(Confirmation::Confirmed(command)),
+        }
+    }
+}
+
+/// A principal confirming commands.
+pub trait ConfirmationCaller {
+    /// The id of the principal. Only the principal a command is asked to can confirm it.
+    fn confirmer_id(&self) -> String;
+}
+
+/// The command confirmed with a token, the caller asked, and the time the token expires at.
+struct Issued {
+    command: Vec<&'static str>,
+    caller: String,
+    fragments: Vec<CommandFragment>,
+    expire: Instant,
+}
+
+/// An in-memory tracker of the confirmation tokens issued.
+///
+/// Each token is random, confirms only the command and the caller it is issued for,
+/// expires after the time to live, and is used up by the confirmation.
+/// The tokens are not kept anywhere else, so [`PendingConfirmation`] stored across a restart cannot be confirmed.
+pub struct ConfirmationTracker<C = SystemClock> {
+    clock: C,
+    ttl: Duration,
+    secret: RandomState,
+    counter: AtomicU64,
+    issued: Mutex<HashMap<String, Issued>>,
+}
+
+impl ConfirmationTracker {
+    /// Make a new [`ConfirmationTracker`] with [`SystemClock`] and the time to live of tokens.
+    pub fn new(ttl: Duration) -> Self {
+        ConfirmationTracker::with_clock(ttl, SystemClock)
+    }
+}
+
+impl<C: Clock> ConfirmationTracker<C> {
+    /// Make a new [`ConfirmationTracker`] with the time to live of tokens and the clock given.
+    pub fn with_clock(ttl: Duration, clock: C) -> Self {
+        ConfirmationTracker {
+            clock,
+            ttl,
+            secret: RandomState::new(),
+            counter: AtomicU64::new(0),
+            issued: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Forget the tokens already expired.
+    pub fn clear_expired(&self) {
+        let now = self.clock.now();
+        self.issued().retain(|_, issued| issued.expire > now);
+    }
+
+    /// Issue a new token for the command and the caller, forgetting the tokens already expired.
+    fn issue(
+        &self,
+        command: Vec<&'static str>,
+        caller: &str,
+        fragments: Vec<CommandFragment>,
+    ) -> String {
+        let now = self.clock.now();
+        let mut issued = self.issued();
+        issued.retain(|_, issued| issued.expire > now);
+        let token = loop {
+            // The token starts with a letter not to be lexed as a number.
+            let token = format!(
+                "c{:016x}",
+                self.secret
+                    .hash_one(self.counter.fetch_add(1, Ordering::Relaxed))
+            );
+            if !issued.contains_key(&token) {
+                break token;
+            }
+        };
+        issued.insert(
+            token.clone(),
+            Issued {
+                command,
+                caller: caller.to_string(),
+                fragments,
+                expire: now + self.ttl,
+            },
+        );
+        token
+    }
+
+    /// Use up the token if it is issued for the command and the caller, and not expired yet.
+    fn consume(
+        &self,
+        token: &str,
+        command: &[&'static str],
+        caller: &str,
+        fragments: &[CommandFragment],
+    ) -> bool {
+        let now = self.clock.now();
+        let mut issued = self.issued();
+        let matched = issued.get(token).is_some_and(|issued| {
+            issued.expire > now
+                && issued.command == command
+                && issued.caller == caller
+                && issued.fragments == fragments
+        });
+        if matched {
+            issued.remove(token);
+        }
+        matched
+    }
+
+    /// Lock the tokens issued, recovering them from a panic in another thread
+    /// as they are valid between each update.
+    fn issued(&self) -> MutexGuard<'_, HashMap<String, Issued>> {
+        self.issued
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Make [`PendingConfirmation`] if the command selected at the end of the path needs confirmation
+/// and `fragments` doesn't carry the token issued for `command_fragments` and the caller.
+/// The confirmation of the commands above isn't asked for it.
+pub(crate) fn pending_confirmation(
+    path: &[&CommandSpec],
+    command_fragments: Vec<CommandFragment>,
+    fragments: &[CommandFragment],
+    tracker: &ConfirmationTracker<impl Clock>,
+    caller: &str,
+) -> Option<PendingConfirmation> {
+    let spec = path[path.len() - 1];
+    let template = spec.self_execution.confirm.or(spec.confirm)?;
+
+    let command: Vec<_> = path.iter().map(|spec| spec.name).collect();
+    if let Some(token) = given_token(fragments) {
+        if tracker.consume(token, &command, caller, &command_fragments) {
+            return None;
+        }
+    }
+    let token = tracker.issue(command, caller, command_fragments.clone());
+
+    let message = fill_message(template, spec, &command_fragments);
+    let mut confirmed = command_fragments;
+    if let Some(CommandFragment::Execute(arguments)) = confirmed.last_mut() {
+        arguments.push(CommandArgument::Named(
+            CONFIRM_ARGUMENT.to_string(),
+            CommandArgumentValue::String(token.clone()),
+        ));
+    }
+    Some(PendingConfirmation {
+        token,
+        message,
+        confirmed,
+    })
+}
+
+fn given_token(fragments: &[CommandFragment]) -> Option<&str> {
+    let Some(CommandFragment::Execute(arguments)) = fragments.last() else {
+        return None;
+    };
+    arguments.iter().find_map(|argument| match argument {
+        CommandArgument::Named(name, CommandArgumentValue::String(token))
+            if name == CONFIRM_ARGUMENT =>
+        {
+            Some(token.as_str())
+        }
+        _ => None,
+    })
+}
+
+/// Replace each `{name}` in the template with the value of argument named.
+fn fill_message(template: &str, spec: &CommandSpec, fragments: &[CommandFragment]) -> String {
+    let Some(CommandFragment::Execute(arguments)) = fragments.last() else {
+        return template.to_string();
+    };
+    let mut message = template.to_string();
+    for argument in arguments {
+        let (name, value) = match argument {
+            CommandArgument::Named(name, value) => (name.as_str(), value),
+            CommandArgument::Positioned(position, value) => {
+                match spec
+                    .options
+                    .iter()
+                    .find(|option| option.position == *position)
+                {
+                    Some(option) => (option.name, value),
+                    None => continue,
+                }
+            }
+        };
+        let value = match value {
+            CommandArgumentValue::String(s) => s.clone(),
+            CommandArgumentValue::I64(i) => i.to_string(),
+            CommandArgumentValue::F64(f) => f.to_string(),
+        };
+        message = message.replace(&format!("{{{}}}", name), &value);
+    }
+    message
+}
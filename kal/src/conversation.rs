@@ -0,0 +1,151 @@
+use core::fmt;
+
+use crate::{
+    Command, CommandArgument, CommandArgumentValue, CommandFragment, CommandOption,
+    CommandOptionValueKind, CommandParseError, CommandSpec,
+};
+
+/// A conversation filling the missing arguments of a command one by one.
+///
+/// It holds the [`CommandFragment`] sequence partially filled,
+/// and asks the option missing first whenever the command cannot be parsed for missing arguments.
+/// With `"serde"` feature flag, it can be serialized to survive restarts.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Conversation {
+    fragments: Vec<CommandFragment>,
+    asking: Option<String>,
+}
+
+/// The next step of [`Conversation`].
+#[derive(Debug, PartialEq)]
+pub enum ConversationStep<C> {
+    /// Every argument is filled and the command is parsed.
+    Done(C),
+
+    /// The argument should be replied.
+    Ask(Prompt),
+}
+
+/// A prompt asking an argument value.
+#[derive(Debug, PartialEq)]
+pub struct Prompt {
+    /// The option asked
+    pub option: &'static CommandOption,
+}
+
+impl fmt::Display for Prompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.option.value.as_primitive() {
+            CommandOptionValueKind::Integer => "integer",
+            CommandOptionValueKind::Double => "number",
+            _ => "text",
+        };
+        if self.option.description.is_empty() {
+            write!(f, "Enter {} ({})", self.option.name, kind)
+        } else {
+            write!(
+                f,
+                "Enter {} ({}): {}",
+                self.option.name, kind, self.option.description
+            )
+        }
+    }
+}
+
+impl Conversation {
+    /// Start a conversation with the [`CommandFragment`] sequence [`Command::parse`] takes.
+    /// The sequence may end without [`CommandFragment::Execute`] to ask every argument.
+    pub fn new(mut fragments: Vec<CommandFragment>) -> Self {
+        if !matches!(fragments.last(), Some(CommandFragment::Execute(_))) {
+            fragments.push(CommandFragment::Execute(Vec::new()));
+        }
+        Conversation {
+            fragments,
+            asking: None,
+        }
+    }
+
+    /// The [`CommandFragment`] sequence filled so far.
+    pub fn fragments(&self) -> &[CommandFragment] {
+        &self.fragments
+    }
+
+    /// The name of argument asked now.
+    pub fn asking(&self) -> Option<&str> {
+        self.asking.as_deref()
+    }
+
+    /// Parse the command, or ask the argument missing first.
+    pub fn poll<C: Command + 'static>(
+        &mut self,
+    ) -> Result<ConversationStep<C>, CommandParseError<'_>> {
+        let missing = match C::parse(&self.fragments) {
+            Ok(command) => {
+                self.asking = None;
+                return Ok(ConversationStep::Done(command));
+            }
+            Err(CommandParseError::MissingArguments(missing)) => missing[0].to_string(),
+            Err(error) => return Err(error),
+        };
+        let option = find_option(selected_spec::<C>(&self.fragments), &missing)
+            .ok_or(CommandParseError::IncompleteCommand)?;
+        self.asking = Some(missing);
+        Ok(ConversationStep::Ask(Prompt { option }))
+    }
+
+    /// Fill the argument asked with the reply, then parse the command again.
+    /// The reply is rejected if it is not of the option value kind or does not pass the option validation,
+    /// and the argument will be asked again.
+    pub fn reply<C: Command + 'static>(
+        &mut self,
+        reply: &str,
+    ) -> Result<ConversationStep<C>, CommandParseError<'_>> {
+        let option = match &self.asking {
+            Some(asking) => find_option(selected_spec::<C>(&self.fragments), asking)
+                .ok_or(CommandParseError::IncompleteCommand)?,
+            None => return self.poll(),
+        };
+        let value = parse_reply(&option.value, reply.trim()).map_err(|reason| {
+            CommandParseError::ValidationFailed {
+                argument: option.name,
+                reason,
+            }
+        })?;
+        option
+            .validation
+            .check(&value)
+            .map_err(|reason| CommandParseError::ValidationFailed {
+                argument: option.name,
+                reason,
+            })?;
+
+        let argument = CommandArgument::Named(option.name.to_string(), value);
+        if let Some(CommandFragment::Execute(arguments)) = self.fragments.last_mut() {
+            arguments.push(argument);
+        }
+        self.poll()
+    }
+}
+
+fn selected_spec<C: Command + 'static>(fragments: &[CommandFragment]) -> &'static CommandSpec {
+    C::spec_ref().select_path(fragments).pop().unwrap()
+}
+
+fn find_option(spec: &'static CommandSpec, name: &str) -> Option<&'static CommandOption> {
+    spec.options.iter().find(|option| option.name == name)
+}
+
+fn parse_reply(kind: &CommandOptionValueKind, reply: &str) -> Result<CommandArgumentValue, String> {
+    match kind.as_primitive() {
+        CommandOptionValueKind::Integer => reply
+            .parse()
+            .map(CommandArgumentValue::I64)
+            .map_err(|_| "must be an integer".to_string()),
+        CommandOptionValueKind::Double => reply
+            .parse()
+            .map(CommandArgumentValue::F64)
+            .map_err(|_| "must be a number".to_string()),
+        _ => Ok(CommandArgumentValue::String(reply.to_string())),
+    }
+}
@@ -0,0 +1,188 @@
+use core::fmt;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use crate::CommandSpec;
+
+/// The cooldown between each run of a command
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandCooldown {
+    /// The duration the command cannot be run again
+    pub duration: Duration,
+
+    /// The scope the cooldown is shared in, like `"user"`, `"channel"` or `"global"`
+    pub per: &'static str,
+}
+
+/// A principal running commands, identified in each cooldown scope.
+pub trait CooldownCaller {
+    /// The id of the principal in the scope, like the user id for `"user"`.
+    /// The id is ignored for `"global"` scope.
+    fn caller_id(&self, per: &str) -> String;
+}
+
+/// A source of the current time for [`CooldownTracker`] and [`ConfirmationTracker`](crate::ConfirmationTracker).
+pub trait Clock {
+    /// The current time.
+    fn now(&self) -> Instant;
+}
+
+/// The [`Clock`] reading the system time.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+impl<F: Fn() -> Instant> Clock for F {
+    fn now(&self) -> Instant {
+        self()
+    }
+}
+
+/// The command is on cooldown.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryAfter {
+    /// The path of command names the cooldown is set on
+    pub command: Vec<&'static str>,
+
+    /// The duration left until the command can be run again
+    pub remaining: Duration,
+}
+
+impl fmt::Display for RetryAfter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Retry {} after {:.1}s",
+            self.command.join(" "),
+            self.remaining.as_secs_f64()
+        )
+    }
+}
+
+/// The command path, whether it is for executing the command itself, and the caller id.
+type CooldownKey = (Vec<&'static str>, bool, String);
+
+/// An in-memory tracker of [`CommandCooldown`] keyed by command path and caller id.
+pub struct CooldownTracker<C = SystemClock> {
+    clock: C,
+    expires: Mutex<HashMap<CooldownKey, Instant>>,
+}
+
+impl CooldownTracker {
+    /// Make a new [`CooldownTracker`] with [`SystemClock`].
+    pub fn new() -> Self {
+        CooldownTracker::with_clock(SystemClock)
+    }
+}
+
+impl Default for CooldownTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Clock> CooldownTracker<C> {
+    /// Make a new [`CooldownTracker`] with the clock given.
+    pub fn with_clock(clock: C) -> Self {
+        CooldownTracker {
+            clock,
+            expires: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Check every cooldown on the [`CommandSpec`] path selected without starting them.
+    /// A cooldown set on a command is shared with its subcommands,
+    /// but [`CommandSelfExecution::cooldown`](`crate::CommandSelfExecution::cooldown`) is only for the command executed.
+    pub fn check(
+        &self,
+        path: &[&CommandSpec],
+        caller: &(impl CooldownCaller + ?Sized),
+    ) -> Result<(), RetryAfter> {
+        let now = self.clock.now();
+        let expires = self.expires();
+        for (key, _) in keys(path, caller) {
+            retry_after(&expires, &key, now)?;
+        }
+        Ok(())
+    }
+
+    /// Check every cooldown on the [`CommandSpec`] path selected like [`CooldownTracker::check`],
+    /// and start them if none of them is running.
+    /// The cooldowns already expired are forgotten meanwhile.
+    pub fn start(
+        &self,
+        path: &[&CommandSpec],
+        caller: &(impl CooldownCaller + ?Sized),
+    ) -> Result<(), RetryAfter> {
+        let now = self.clock.now();
+        let keys = keys(path, caller);
+        let mut expires = self.expires();
+        expires.retain(|_, expire| *expire > now);
+        for (key, _) in &keys {
+            retry_after(&expires, key, now)?;
+        }
+        for (key, duration) in keys {
+            expires.insert(key, now + duration);
+        }
+        Ok(())
+    }
+
+    /// Forget the cooldowns already expired.
+    pub fn clear_expired(&self) {
+        let now = self.clock.now();
+        self.expires().retain(|_, expire| *expire > now);
+    }
+
+    /// Lock the expiration times, recovering them from a panic in another thread
+    /// as they are valid between each update.
+    fn expires(&self) -> MutexGuard<'_, HashMap<CooldownKey, Instant>> {
+        self.expires
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// The keys of cooldowns on the path with their durations.
+fn keys(
+    path: &[&CommandSpec],
+    caller: &(impl CooldownCaller + ?Sized),
+) -> Vec<(CooldownKey, Duration)> {
+    let cooldowns =
+        (0..path.len())
+            .filter_map(|depth| Some((depth, false, path[depth].cooldown.as_ref()?)))
+            .chain(path.last().and_then(|spec| {
+                Some((path.len() - 1, true, spec.self_execution.cooldown.as_ref()?))
+            }));
+    cooldowns
+        .map(|(depth, for_self, cooldown)| {
+            let command: Vec<_> = path[..=depth].iter().map(|spec| spec.name).collect();
+            let id = match cooldown.per {
+                "global" => String::new(),
+                per => caller.caller_id(per),
+            };
+            ((command, for_self, id), cooldown.duration)
+        })
+        .collect()
+}
+
+fn retry_after(
+    expires: &HashMap<CooldownKey, Instant>,
+    key: &CooldownKey,
+    now: Instant,
+) -> Result<(), RetryAfter> {
+    match expires.get(key) {
+        Some(expire) if *expire > now => Err(RetryAfter {
+            command: key.0.clone(),
+            remaining: *expire - now,
+        }),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,59 @@
+use std::{any::Any, ops::ControlFlow};
+
+use crate::{Clock, CommandSpec, CooldownCaller, CooldownTracker, RetryAfter};
+
+use super::{BoxFuture, Middleware, Next};
+
+/// A [`Middleware`] stopping the command on cooldown for the context.
+///
+/// Cooldowns on the [`CommandSpec`] path selected are checked with [`CooldownTracker::check`] after the parse,
+/// and started with [`CooldownTracker::start`] only when the command reaches its handler,
+/// so the commands stopped by later middleware or waiting for confirmation don't start them.
+/// The output for the command on cooldown is made by `reply`.
+pub struct Cooldowns<C, F> {
+    tracker: CooldownTracker<C>,
+    reply: F,
+}
+
+impl<C, F> Cooldowns<C, F> {
+    /// Make a new [`Cooldowns`] tracking with `tracker` and replying with `reply` for the command on cooldown.
+    pub fn new(tracker: CooldownTracker<C>, reply: F) -> Self {
+        Cooldowns { tracker, reply }
+    }
+}
+
+impl<Ctx, O, C, F> Middleware<Ctx, O> for Cooldowns<C, F>
+where
+    Ctx: CooldownCaller + Sync,
+    O: Send + 'static,
+    C: Clock + Send + Sync,
+    F: Fn(RetryAfter, &Ctx) -> O + Send + Sync,
+{
+    fn after_parse<'a>(
+        &'a self,
+        path: &'a [&'static CommandSpec],
+        _command: &'a (dyn Any + Send + Sync),
+        ctx: &'a Ctx,
+    ) -> BoxFuture<'a, ControlFlow<O>> {
+        let flow = match self.tracker.check(path, ctx) {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(retry_after) => ControlFlow::Break((self.reply)(retry_after, ctx)),
+        };
+        Box::pin(async { flow })
+    }
+
+    fn around_handle<'a>(
+        &'a self,
+        path: &'a [&'static CommandSpec],
+        ctx: &'a Ctx,
+        next: Next<'a, O>,
+    ) -> BoxFuture<'a, O> {
+        match self.tracker.start(path, ctx) {
+            Ok(()) => next.run(),
+            Err(retry_after) => {
+                let output = (self.reply)(retry_after, ctx);
+                Box::pin(async { output })
+            }
+        }
+    }
+}
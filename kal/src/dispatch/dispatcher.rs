@@ -0,0 +1,301 @@
+use std::{any::Any, collections::HashMap, ops::ControlFlow};
+
+use crate::{
+    confirmation::pending_confirmation, Clock, CommandFragment, CommandParseError, CommandSpec,
+    ConfirmationCaller, ConfirmationTracker, PendingConfirmation, PermissionChecker, ToFragments,
+};
+
+use super::{BoxFuture, Handler, Middleware, Next};
+
+trait Parsed<Ctx, O>: Send + Sync {
+    fn command(&self) -> &(dyn Any + Send + Sync);
+
+    fn to_fragments(&self) -> Vec<CommandFragment>;
+
+    fn handle(self: Box<Self>, ctx: &Ctx) -> BoxFuture<'_, O>;
+}
+
+impl<Ctx, O, H> Parsed<Ctx, O> for H
+where
+    H: Handler<Ctx, Output = O> + ToFragments + Send + Sync + 'static,
+    Ctx: Sync,
+{
+    fn command(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+
+    fn to_fragments(&self) -> Vec<CommandFragment> {
+        ToFragments::to_fragments(self)
+    }
+
+    fn handle(self: Box<Self>, ctx: &Ctx) -> BoxFuture<'_, O> {
+        Box::pin(Handler::handle(*self, ctx))
+    }
+}
+
+type Parse<Ctx, O> =
+    for<'a> fn(&'a [CommandFragment]) -> Result<Box<dyn Parsed<Ctx, O>>, CommandParseError<'a>>;
+
+struct Route<Ctx, O> {
+    spec: &'static CommandSpec,
+    parse: Parse<Ctx, O>,
+}
+
+type Fallback<Ctx, O> = Box<dyn for<'a> Fn(&'a str, &'a Ctx) -> BoxFuture<'a, O> + Send + Sync>;
+
+type AskConfirmation<Ctx> = Box<
+    dyn Fn(
+            &[&CommandSpec],
+            Vec<CommandFragment>,
+            &[CommandFragment],
+            &Ctx,
+        ) -> Option<PendingConfirmation>
+        + Send
+        + Sync,
+>;
+
+type ConfirmationReply<Ctx, O> = Box<dyn Fn(PendingConfirmation, &Ctx) -> O + Send + Sync>;
+
+#[cfg(feature = "lex")]
+type LabelStripper = Box<
+    dyn for<'a> Fn(&'a str) -> Result<&'a str, crate::lex::TokenTransformError<'a>> + Send + Sync,
+>;
+
+/// Parse [`CommandFragment`] sequence and route the command to its [`Handler`].
+///
+/// A command is selected by its name or aliases.
+/// Registering another command with the same name replaces the former one.
+pub struct Dispatcher<Ctx, O = ()> {
+    routes: HashMap<&'static str, Route<Ctx, O>>,
+    specs: Vec<&'static CommandSpec>,
+    fallback: Option<Fallback<Ctx, O>>,
+    confirmation: Option<(AskConfirmation<Ctx>, ConfirmationReply<Ctx, O>)>,
+    middlewares: Vec<Box<dyn Middleware<Ctx, O>>>,
+    #[cfg(feature = "lex")]
+    transformer: crate::lex::TokenTransformer<LabelStripper>,
+}
+
+impl<Ctx, O> Default for Dispatcher<Ctx, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Ctx, O> Dispatcher<Ctx, O> {
+    /// Make a new [`Dispatcher`] without any command.
+    pub fn new() -> Self {
+        Dispatcher {
+            routes: HashMap::new(),
+            specs: Vec::new(),
+            fallback: None,
+            confirmation: None,
+            middlewares: Vec::new(),
+            #[cfg(feature = "lex")]
+            transformer: crate::lex::TokenTransformer::new(
+                None,
+                crate::lex::TransformHint::Select(HashMap::new()),
+            ),
+        }
+    }
+
+    /// Register the command `H` routing to [`Handler::handle`].
+    /// The commands registered before sharing any name or alias with it are replaced.
+    pub fn route<H>(mut self) -> Self
+    where
+        H: Handler<Ctx, Output = O> + ToFragments + Send + Sync + 'static,
+        Ctx: Sync,
+    {
+        let spec = H::spec_ref();
+        // The commands sharing any name or alias are replaced, together with all their routes.
+        let displaced: Vec<&'static CommandSpec> = std::iter::once(&H::NAME)
+            .chain(&spec.aliases)
+            .filter_map(|name| self.routes.get(name).map(|route| route.spec))
+            .collect();
+        self.routes.retain(|_name, route| {
+            let keep = !displaced.iter().any(|d| std::ptr::eq(*d, route.spec));
+            #[cfg(feature = "lex")]
+            if !keep {
+                if let crate::lex::TransformHint::Select(map) = self.transformer.hint_mut() {
+                    map.remove(_name);
+                }
+            }
+            keep
+        });
+        self.specs
+            .retain(|registered| !displaced.iter().any(|d| std::ptr::eq(*d, *registered)));
+        for name in std::iter::once(&H::NAME).chain(&spec.aliases) {
+            self.routes.insert(
+                name,
+                Route {
+                    spec,
+                    parse: parse::<Ctx, O, H>,
+                },
+            );
+        }
+        #[cfg(feature = "lex")]
+        if let crate::lex::TransformHint::Select(map) = self.transformer.hint_mut() {
+            let hint = crate::lex::TransformHint::from(spec);
+            for alias in &spec.aliases {
+                map.insert(alias, hint.clone());
+            }
+            map.insert(H::NAME, hint);
+        }
+        self.specs.push(spec);
+        self
+    }
+
+    /// Set the fallback called with the command name when no command matches it.
+    /// Without the fallback, [`CommandParseError::UnknownCommand`] is returned instead.
+    pub fn fallback<F>(mut self, fallback: F) -> Self
+    where
+        F: for<'a> Fn(&'a str, &'a Ctx) -> BoxFuture<'a, O> + Send + Sync + 'static,
+    {
+        self.fallback = Some(Box::new(fallback));
+        self
+    }
+
+    /// Ask confirmation for the commands with [`CommandSpec::confirm`] set, replying with `reply`.
+    /// The command runs only when the same caller dispatches it again with [`PendingConfirmation::confirmed`]
+    /// before the token issued by `tracker` expires.
+    /// Without it, the commands run without confirmation.
+    pub fn confirmation<C, F>(mut self, tracker: ConfirmationTracker<C>, reply: F) -> Self
+    where
+        Ctx: ConfirmationCaller,
+        C: Clock + Send + Sync + 'static,
+        F: Fn(PendingConfirmation, &Ctx) -> O + Send + Sync + 'static,
+    {
+        let ask = move |path: &[&CommandSpec],
+                        command_fragments: Vec<CommandFragment>,
+                        fragments: &[CommandFragment],
+                        ctx: &Ctx| {
+            pending_confirmation(
+                path,
+                command_fragments,
+                fragments,
+                &tracker,
+                &ctx.confirmer_id(),
+            )
+        };
+        self.confirmation = Some((Box::new(ask), Box::new(reply)));
+        self
+    }
+
+    /// Add the middleware at the end of the chain.
+    pub fn layer<M>(mut self, middleware: M) -> Self
+    where
+        M: Middleware<Ctx, O> + 'static,
+    {
+        self.middlewares.push(Box::new(middleware));
+        self
+    }
+
+    /// The specs of commands registered in registration order.
+    pub fn specs(&self) -> impl Iterator<Item = &'static CommandSpec> + '_ {
+        self.specs.iter().copied()
+    }
+
+    /// The specs of commands registered pruned to what the principal is permitted to run.
+    /// See [`CommandSpec::visible_to`].
+    pub fn visible_specs(&self, checker: &(impl PermissionChecker + ?Sized)) -> Vec<CommandSpec> {
+        self.specs
+            .iter()
+            .filter_map(|spec| spec.visible_to(checker))
+            .collect()
+    }
+
+    /// Dispatch the [`CommandFragment`] sequence starting with [`CommandFragment::Select`] of the command name.
+    /// The command is handled through the middleware chain.
+    pub async fn dispatch<'a>(
+        &'a self,
+        fragments: &'a [CommandFragment],
+        ctx: &'a Ctx,
+    ) -> Result<O, CommandParseError<'a>> {
+        for middleware in &self.middlewares {
+            if let ControlFlow::Break(output) = middleware.before_parse(fragments, ctx).await {
+                return Ok(output);
+            }
+        }
+
+        let error = match fragments {
+            [CommandFragment::Select(name), rest @ ..] => match self.routes.get(name.as_str()) {
+                Some(route) => match (route.parse)(rest) {
+                    Ok(parsed) => {
+                        let path = route.spec.select_path(rest);
+                        for middleware in &self.middlewares {
+                            if let ControlFlow::Break(output) =
+                                middleware.after_parse(&path, parsed.command(), ctx).await
+                            {
+                                return Ok(output);
+                            }
+                        }
+                        if let Some((ask, reply)) = &self.confirmation {
+                            if let Some(mut pending) = ask(&path, parsed.to_fragments(), rest, ctx)
+                            {
+                                pending
+                                    .confirmed
+                                    .insert(0, CommandFragment::Select(name.clone()));
+                                return Ok(reply(pending, ctx));
+                            }
+                        }
+                        let mut next = Next::new(parsed.handle(ctx));
+                        for middleware in self.middlewares.iter().rev() {
+                            next = Next::new(middleware.around_handle(&path, ctx, next));
+                        }
+                        return Ok(next.run().await);
+                    }
+                    Err(error) => error,
+                },
+                None => match &self.fallback {
+                    Some(fallback) => return Ok(fallback(name, ctx).await),
+                    None => CommandParseError::UnknownCommand(name),
+                },
+            },
+            [CommandFragment::Execute(_), ..] => CommandParseError::ExecuteTooEarly,
+            [] => CommandParseError::IncompleteCommand,
+        };
+
+        for middleware in &self.middlewares {
+            if let Some(output) = middleware.on_error(&error, ctx).await {
+                return Ok(output);
+            }
+        }
+        Err(error)
+    }
+}
+
+#[cfg(feature = "lex")]
+impl<Ctx, O> Dispatcher<Ctx, O> {
+    /// Set the label stripper applied to the command name of raw messages, like [`TokenTransformer::command_group`](`crate::lex::TokenTransformer::command_group`).
+    pub fn label_stripper<F>(mut self, label_stripper: F) -> Self
+    where
+        F: for<'a> Fn(&'a str) -> Result<&'a str, crate::lex::TokenTransformError<'a>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.transformer
+            .set_label_stripper(Box::new(label_stripper));
+        self
+    }
+
+    /// Transform the raw message into [`CommandFragment`] sequence for [`Dispatcher::dispatch`]
+    /// with hints made from the commands registered.
+    pub fn transform<'a>(
+        &'a self,
+        message: &'a str,
+    ) -> Result<Vec<CommandFragment>, crate::lex::TokenTransformError<'a>> {
+        self.transformer
+            .transform(crate::lex::CommandLexer::new(message))
+            .collect()
+    }
+}
+
+fn parse<Ctx, O, H>(
+    fragments: &[CommandFragment],
+) -> Result<Box<dyn Parsed<Ctx, O>>, CommandParseError<'_>>
+where
+    H: Handler<Ctx, Output = O> + ToFragments + Send + Sync + 'static,
+    Ctx: Sync,
+{
+    Ok(Box::new(H::parse(fragments)?))
+}
@@ -0,0 +1,19 @@
+use std::{future::Future, pin::Pin};
+
+use crate::Command;
+
+/// A boxed future which can be sent between threads.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A command which can be handled with the context `Ctx`.
+///
+/// Implement it for each command type and register the type to [`Dispatcher`](`super::Dispatcher`).
+/// Both `fn handle(..) -> impl Future` and `async fn handle(..)` are allowed
+/// as long as the future returned is [`Send`].
+pub trait Handler<Ctx>: Command {
+    /// The output of handling the command.
+    type Output;
+
+    /// Handle the command parsed.
+    fn handle(self, ctx: &Ctx) -> impl Future<Output = Self::Output> + Send;
+}
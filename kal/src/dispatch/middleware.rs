@@ -0,0 +1,76 @@
+use std::{any::Any, ops::ControlFlow};
+
+use crate::{CommandFragment, CommandParseError, CommandSpec};
+
+use super::BoxFuture;
+
+/// A hook chain around the command execution of [`Dispatcher`](`super::Dispatcher`).
+///
+/// Each hook is called in the order of [`Dispatcher::layer`](`super::Dispatcher::layer`).
+/// Returning [`ControlFlow::Break`] stops the chain and the dispatcher returns its output instead of handling the command.
+/// [`Middleware::around_handle`] wraps the handler instead, the first layer being the outermost.
+/// Every hook does nothing by default.
+pub trait Middleware<Ctx, O>: Send + Sync {
+    /// Called before the command is parsed.
+    fn before_parse<'a>(
+        &'a self,
+        fragments: &'a [CommandFragment],
+        ctx: &'a Ctx,
+    ) -> BoxFuture<'a, ControlFlow<O>> {
+        let _ = (fragments, ctx);
+        Box::pin(async { ControlFlow::Continue(()) })
+    }
+
+    /// Called after the command is parsed, before it is handled.
+    /// The `path` is the [`CommandSpec`] nodes selected from the registered command to the command executed,
+    /// and the `command` is the parsed command which can be downcasted to its type.
+    fn after_parse<'a>(
+        &'a self,
+        path: &'a [&'static CommandSpec],
+        command: &'a (dyn Any + Send + Sync),
+        ctx: &'a Ctx,
+    ) -> BoxFuture<'a, ControlFlow<O>> {
+        let _ = (path, command, ctx);
+        Box::pin(async { ControlFlow::Continue(()) })
+    }
+
+    /// Called to handle the command after the parse hooks and the confirmation,
+    /// with `next` running the rest of the chain and the handler at last.
+    /// The middleware can time or skip `next`, and post-process its output.
+    fn around_handle<'a>(
+        &'a self,
+        path: &'a [&'static CommandSpec],
+        ctx: &'a Ctx,
+        next: Next<'a, O>,
+    ) -> BoxFuture<'a, O> {
+        let _ = (path, ctx);
+        next.run()
+    }
+
+    /// Called when the command cannot be parsed nor routed.
+    /// Returning `Some` replies the error with the output instead of returning it.
+    fn on_error<'a>(
+        &'a self,
+        error: &'a CommandParseError<'a>,
+        ctx: &'a Ctx,
+    ) -> BoxFuture<'a, Option<O>> {
+        let _ = (error, ctx);
+        Box::pin(async { None })
+    }
+}
+
+/// The rest of the middleware chain handling the command, given to [`Middleware::around_handle`].
+pub struct Next<'a, O> {
+    future: BoxFuture<'a, O>,
+}
+
+impl<'a, O> Next<'a, O> {
+    pub(crate) fn new(future: BoxFuture<'a, O>) -> Self {
+        Next { future }
+    }
+
+    /// Run the rest of the chain, which does nothing until awaited.
+    pub fn run(self) -> BoxFuture<'a, O> {
+        self.future
+    }
+}
@@ -0,0 +1,77 @@
+//! The dispatch module routes commands parsed to their handlers.
+//!
+//! Implement [`Handler`] for each command type with the context type you like,
+//! then register them to [`Dispatcher`].
+//! The commands should implement [`ToFragments`](`crate::ToFragments`) too, which the derive does with `#[command(to_fragments)]`.
+//! The dispatcher parses [`CommandFragment`](`crate::CommandFragment`) sequence with [`Command::parse`](`crate::Command::parse`)
+//! and calls the handler of the command selected.
+//! Commands unknown to the dispatcher go to the fallback if set.
+//!
+//! It returns a plain [`Future`](`std::future::Future`), so any async runtime can drive it.
+//!
+//! ```rust
+//! # use kal::{Command, CommandFragment, CommandArgument, CommandArgumentValue};
+//! # use kal::dispatch::{Dispatcher, Handler};
+//! # #[cfg_attr(feature = "lex", derive(kal::lex::TransformHintProvider))]
+//! #[derive(Command)]
+//! #[command(to_fragments)]
+//! struct Echo {
+//!     text: String,
+//! }
+//!
+//! struct Context {
+//!     author: String,
+//! }
+//!
+//! impl Handler<Context> for Echo {
+//!     type Output = String;
+//!
+//!     async fn handle(self, ctx: &Context) -> String {
+//!         format!("{}: {}", ctx.author, self.text)
+//!     }
+//! }
+//!
+//! let dispatcher = Dispatcher::new()
+//!     .route::<Echo>()
+//!     .fallback(|name, _| Box::pin(async move { format!("Unknown command {}", name) }));
+//! let ctx = Context { author: "kal".to_string() };
+//!
+//! let fragments = vec![
+//!     CommandFragment::Select("echo".to_string()),
+//!     CommandFragment::Execute(vec![CommandArgument::Positioned(
+//!         0,
+//!         CommandArgumentValue::String("hello".to_string()),
+//!     )]),
+//! ];
+//! assert_eq!(
+//!     pollster::block_on(dispatcher.dispatch(&fragments, &ctx)),
+//!     Ok("kal: hello".to_string()),
+//! );
+//!
+//! let fragments = vec![CommandFragment::Select("ping".to_string())];
+//! assert_eq!(
+//!     pollster::block_on(dispatcher.dispatch(&fragments, &ctx)),
+//!     Ok("Unknown command ping".to_string()),
+//! );
+//! ```
+//!
+//! Cross-cutting behaviors like logging or error replies can be plugged in as [`Middleware`] with [`Dispatcher::layer`].
+//! The middleware hooks before the parse, after the parse with the [`CommandSpec`](`crate::CommandSpec`) path selected,
+//! around the handler with [`Next`], and on the error, and it can stop the command by replying its own output.
+//! For example, [`RequirePermissions`] stops the commands the context is not permitted to run,
+//! and [`Cooldowns`] stops the commands on cooldown.
+//! After the middleware chain, [`Dispatcher::confirmation`] asks confirmation for dangerous commands.
+//!
+//! With `"lex"` feature flag, [`Dispatcher::transform`] turns a raw message into the fragments
+//! with hints made from the commands registered.
+pub use cooldown::Cooldowns;
+pub use dispatcher::Dispatcher;
+pub use handler::{BoxFuture, Handler};
+pub use middleware::{Middleware, Next};
+pub use permission::RequirePermissions;
+
+mod cooldown;
+mod dispatcher;
+mod handler;
+mod middleware;
+mod permission;
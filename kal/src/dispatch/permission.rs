@@ -0,0 +1,48 @@
+use std::{any::Any, ops::ControlFlow};
+
+use crate::{CommandSpec, PermissionChecker};
+
+use super::{BoxFuture, Middleware};
+
+/// A [`Middleware`] stopping the command the context is not permitted to run.
+///
+/// Every [`CommandSpec`] on the path selected is checked with the context as [`PermissionChecker`],
+/// together with [`CommandSelfExecution::permissions`](`crate::CommandSelfExecution::permissions`) of the command executed,
+/// and the output for the first spec forbidden is made by `deny`.
+pub struct RequirePermissions<F> {
+    deny: F,
+}
+
+impl<F> RequirePermissions<F> {
+    /// Make a new [`RequirePermissions`] replying with `deny` for forbidden commands.
+    pub fn new(deny: F) -> Self {
+        RequirePermissions { deny }
+    }
+}
+
+impl<Ctx, O, F> Middleware<Ctx, O> for RequirePermissions<F>
+where
+    Ctx: PermissionChecker + Sync,
+    O: Send + 'static,
+    F: Fn(&CommandSpec, &Ctx) -> O + Send + Sync,
+{
+    fn after_parse<'a>(
+        &'a self,
+        path: &'a [&'static CommandSpec],
+        _command: &'a (dyn Any + Send + Sync),
+        ctx: &'a Ctx,
+    ) -> BoxFuture<'a, ControlFlow<O>> {
+        let executed = path
+            .last()
+            .filter(|spec| !ctx.is_permitted(&spec.self_execution.permissions));
+        let flow = match path
+            .iter()
+            .find(|spec| !ctx.is_permitted(&spec.permissions))
+            .or(executed)
+        {
+            Some(spec) => ControlFlow::Break((self.deny)(spec, ctx)),
+            None => ControlFlow::Continue(()),
+        };
+        Box::pin(async { flow })
+    }
+}
@@ -0,0 +1,171 @@
+//! Commands defined at runtime, without deriving [`Command`](`crate::Command`).
+
+use std::collections::HashMap;
+
+use crate::{
+    CommandArgument, CommandArgumentValue, CommandFragment, CommandOptionValueKind,
+    CommandParseError, TryFromArgumentValue,
+};
+
+pub use registry::DynCommandRegistry;
+pub use spec::{DynCommandOption, DynCommandSpec, DynCommandSpecBuilder, DynCommandSpecError};
+
+mod registry;
+mod spec;
+
+/// The command parsed with [`DynCommandSpec`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedInvocation {
+    /// The names of commands selected, starting with the command parsed
+    pub path: Vec<String>,
+
+    /// The values of arguments given by option name
+    pub args: HashMap<String, CommandArgumentValue>,
+}
+
+impl ParsedInvocation {
+    /// Make [`CommandFragment`] sequence selecting the path and giving the arguments by name,
+    /// which the derived command of the path can also parse.
+    pub fn to_fragments(&self) -> Vec<CommandFragment> {
+        let mut args: Vec<_> = self.args.iter().collect();
+        args.sort_by_key(|(name, _)| *name);
+        let mut fragments: Vec<_> = self
+            .path
+            .iter()
+            .map(|name| CommandFragment::Select(name.clone()))
+            .collect();
+        fragments.push(CommandFragment::Execute(
+            args.into_iter()
+                .map(|(name, value)| CommandArgument::Named(name.clone(), value.clone()))
+                .collect(),
+        ));
+        fragments
+    }
+}
+
+impl DynCommandSpec {
+    /// Parse [`CommandFragment`] sequence following the name of command, like [`Command::parse`](`crate::Command::parse`).
+    ///
+    /// The arguments are checked the same as derived commands:
+    /// the validation of each value, the exclusive groups, the requirements and the missing arguments.
+    pub fn parse<'a>(
+        &'a self,
+        fragments: &'a [CommandFragment],
+    ) -> Result<ParsedInvocation, CommandParseError<'a>> {
+        let mut path = vec![self.name.clone()];
+        let mut spec = self;
+        let mut fragments = fragments;
+        loop {
+            match fragments {
+                [CommandFragment::Select(name), rest @ ..] => match spec.subcommand(name) {
+                    Some(subcommand) => {
+                        path.push(subcommand.name.clone());
+                        spec = subcommand;
+                        fragments = rest;
+                    }
+                    None => return Err(CommandParseError::UnknownCommand(name)),
+                },
+                [CommandFragment::Execute(arguments), ..]
+                    if spec.subcommands.is_empty() || !spec.options.is_empty() =>
+                {
+                    let args = spec.parse_arguments(arguments)?;
+                    return Ok(ParsedInvocation { path, args });
+                }
+                [CommandFragment::Execute(_), ..] => {
+                    return Err(CommandParseError::ExecuteTooEarly)
+                }
+                [] => return Err(CommandParseError::IncompleteCommand),
+            }
+        }
+    }
+
+    fn parse_arguments<'a>(
+        &'a self,
+        arguments: &[CommandArgument],
+    ) -> Result<HashMap<String, CommandArgumentValue>, CommandParseError<'a>> {
+        let mut values = HashMap::new();
+        for argument in arguments {
+            let (option, value) = match argument {
+                CommandArgument::Named(name, value) => (
+                    self.options.iter().find(|option| &option.name == name),
+                    value,
+                ),
+                CommandArgument::Positioned(position, value) => (
+                    self.options
+                        .iter()
+                        .find(|option| option.position == *position),
+                    value,
+                ),
+            };
+            let Some(option) = option else {
+                continue;
+            };
+            let value = convert(&option.value, value);
+            if let Some(Err(reason)) = value.as_ref().map(|value| option.validation.check(value)) {
+                return Err(CommandParseError::ValidationFailed {
+                    argument: &option.name,
+                    reason,
+                });
+            }
+            values.insert(option.name.as_str(), value);
+        }
+
+        let provided = |name: &str| values.contains_key(name);
+        for group in &self.exclusive {
+            let conflicting: Vec<&str> = group
+                .iter()
+                .map(String::as_str)
+                .filter(|name| provided(name))
+                .collect();
+            if conflicting.len() > 1 {
+                return Err(CommandParseError::ConflictingArguments(conflicting));
+            }
+        }
+        for option in &self.options {
+            for required in &option.requires {
+                if provided(&option.name) && !provided(required) {
+                    return Err(CommandParseError::MissingRequiredArgument {
+                        argument: &option.name,
+                        requires: required,
+                    });
+                }
+            }
+        }
+
+        let mut args = HashMap::new();
+        let mut missing_arguments = Vec::new();
+        for option in &self.options {
+            match values.remove(option.name.as_str()) {
+                Some(Some(value)) => {
+                    args.insert(option.name.clone(), value);
+                }
+                None if option.value.is_optional() => {}
+                _ => missing_arguments.push(option.name.as_str()),
+            }
+        }
+        if !missing_arguments.is_empty() {
+            return Err(CommandParseError::MissingArguments(missing_arguments));
+        }
+        Ok(args)
+    }
+}
+
+/// Convert the value to the kind of option, or `None` if it cannot be.
+fn convert(
+    kind: &CommandOptionValueKind,
+    value: &CommandArgumentValue,
+) -> Option<CommandArgumentValue> {
+    let value = value.clone();
+    match kind.as_primitive() {
+        CommandOptionValueKind::String => String::try_from_argument_value(value, true)
+            .ok()
+            .map(CommandArgumentValue::String),
+        CommandOptionValueKind::Integer => i64::try_from_argument_value(value, true)
+            .ok()
+            .map(CommandArgumentValue::I64),
+        CommandOptionValueKind::Double => f64::try_from_argument_value(value, true)
+            .ok()
+            .map(CommandArgumentValue::F64),
+        _ => None,
+    }
+}
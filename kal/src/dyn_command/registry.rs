@@ -0,0 +1,77 @@
+use crate::{Command, CommandFragment, CommandParseError};
+
+use super::{DynCommandSpec, DynCommandSpecError, ParsedInvocation};
+
+/// The commands defined at runtime, which can be added and removed while running.
+///
+/// A command is selected by its name or aliases.
+/// Registering another command with the same name replaces the former one.
+#[derive(Clone, Debug, Default)]
+pub struct DynCommandRegistry {
+    commands: Vec<DynCommandSpec>,
+}
+
+impl DynCommandRegistry {
+    /// Make a new [`DynCommandRegistry`] without any command.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the command, returning the former ones sharing any name or alias with it.
+    pub fn register(&mut self, spec: DynCommandSpec) -> Vec<DynCommandSpec> {
+        let (former, commands) =
+            std::mem::take(&mut self.commands)
+                .into_iter()
+                .partition(|command| {
+                    std::iter::once(&command.name)
+                        .chain(&command.aliases)
+                        .any(|name| spec.selected_by(name))
+                });
+        self.commands = commands;
+        self.commands.push(spec);
+        former
+    }
+
+    /// Register the derived command `C` with its spec converted to [`DynCommandSpec`].
+    pub fn register_command<C: Command + 'static>(
+        &mut self,
+    ) -> Result<Vec<DynCommandSpec>, DynCommandSpecError> {
+        Ok(self.register(DynCommandSpec::try_from(C::spec_ref())?))
+    }
+
+    /// Remove the command named `name`.
+    pub fn unregister(&mut self, name: &str) -> Option<DynCommandSpec> {
+        let index = self
+            .commands
+            .iter()
+            .position(|command| command.name == name)?;
+        Some(self.commands.remove(index))
+    }
+
+    /// Find the command selected by its name or aliases.
+    pub fn get(&self, name: &str) -> Option<&DynCommandSpec> {
+        self.commands
+            .iter()
+            .find(|command| command.selected_by(name))
+    }
+
+    /// The specs of commands registered in registration order.
+    pub fn specs(&self) -> impl Iterator<Item = &DynCommandSpec> + '_ {
+        self.commands.iter()
+    }
+
+    /// Parse the [`CommandFragment`] sequence starting with [`CommandFragment::Select`] of the command name.
+    pub fn parse<'a>(
+        &'a self,
+        fragments: &'a [CommandFragment],
+    ) -> Result<ParsedInvocation, CommandParseError<'a>> {
+        match fragments {
+            [CommandFragment::Select(name), rest @ ..] => match self.get(name) {
+                Some(command) => command.parse(rest),
+                None => Err(CommandParseError::UnknownCommand(name)),
+            },
+            [CommandFragment::Execute(_), ..] => Err(CommandParseError::ExecuteTooEarly),
+            [] => Err(CommandParseError::IncompleteCommand),
+        }
+    }
+}
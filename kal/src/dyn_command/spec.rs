@@ -0,0 +1,350 @@
+use core::fmt;
+
+use crate::{
+    CommandOption, CommandOptionValidation, CommandOptionValueKind, CommandPermissions, CommandSpec,
+};
+
+/// The specification of command defined at runtime, owning what [`CommandSpec`] borrows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynCommandSpec {
+    /// The name of command
+    pub name: String,
+
+    /// The description of command
+    pub description: String,
+
+    /// The other names command can be selected with
+    pub aliases: Vec<String>,
+
+    /// The options command can take, each taking a single value
+    pub options: Vec<DynCommandOption>,
+
+    /// The groups of options which cannot be given together
+    pub exclusive: Vec<Vec<String>>,
+
+    /// The subcommands command have
+    pub subcommands: Vec<DynCommandSpec>,
+}
+
+impl DynCommandSpec {
+    /// Start building a command named `name`.
+    pub fn builder(name: impl Into<String>) -> DynCommandSpecBuilder {
+        DynCommandSpecBuilder {
+            spec: DynCommandSpec {
+                name: name.into(),
+                description: String::new(),
+                aliases: Vec::new(),
+                options: Vec::new(),
+                exclusive: Vec::new(),
+                subcommands: Vec::new(),
+            },
+        }
+    }
+
+    /// Find the subcommand selected by its name or aliases.
+    pub fn subcommand(&self, name: &str) -> Option<&DynCommandSpec> {
+        self.subcommands
+            .iter()
+            .find(|subcommand| subcommand.selected_by(name))
+    }
+
+    /// Whether the command is selected by the name or one of its aliases.
+    pub fn selected_by(&self, name: &str) -> bool {
+        self.name == name || self.aliases.iter().any(|alias| alias == name)
+    }
+}
+
+/// The option [`DynCommandSpec`] can take
+///
+/// It cannot take the rest of the command, as there is no hint made from [`DynCommandSpec`] to lex the raw message with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynCommandOption {
+    /// The name of option when it is treated as named argument
+    pub name: String,
+
+    /// The position of option when it is treated as positional argument
+    pub position: usize,
+
+    /// The description of option
+    pub description: String,
+
+    /// The kind of value option can take, either primitive or optional
+    pub value: CommandOptionValueKind,
+
+    /// The constraints the option value should satisfy
+    pub validation: CommandOptionValidation,
+
+    /// The options which should be given together with the option
+    pub requires: Vec<String>,
+}
+
+impl DynCommandOption {
+    /// Make a new option taking the kind of value.
+    /// The position is given by [`DynCommandSpecBuilder::option`].
+    pub fn new(name: impl Into<String>, value: CommandOptionValueKind) -> Self {
+        DynCommandOption {
+            name: name.into(),
+            position: 0,
+            description: String::new(),
+            value,
+            validation: CommandOptionValidation::default(),
+            requires: Vec::new(),
+        }
+    }
+
+    /// Set the description.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Make the option optional.
+    pub fn optional(mut self) -> Self {
+        if !self.value.is_optional() {
+            self.value = CommandOptionValueKind::Optional(Box::new(self.value));
+        }
+        self
+    }
+
+    /// Set the constraints the value should satisfy.
+    pub fn validation(mut self, validation: CommandOptionValidation) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    /// Require the option named `name` to be given together.
+    pub fn requires(mut self, name: impl Into<String>) -> Self {
+        self.requires.push(name.into());
+        self
+    }
+}
+
+/// Build [`DynCommandSpec`], checking it on [`DynCommandSpecBuilder::build`].
+#[derive(Clone, Debug)]
+pub struct DynCommandSpecBuilder {
+    spec: DynCommandSpec,
+}
+
+impl DynCommandSpecBuilder {
+    /// Set the description.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.spec.description = description.into();
+        self
+    }
+
+    /// Add another name the command can be selected with.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.spec.aliases.push(alias.into());
+        self
+    }
+
+    /// Add the option at the next position.
+    pub fn option(mut self, mut option: DynCommandOption) -> Self {
+        option.position = self.spec.options.len();
+        self.spec.options.push(option);
+        self
+    }
+
+    /// Add a group of options which cannot be given together.
+    pub fn exclusive<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.spec
+            .exclusive
+            .push(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Add the subcommand, which can be converted from derived command with [`DynCommandSpec::try_from`].
+    pub fn subcommand(mut self, subcommand: DynCommandSpec) -> Self {
+        self.spec.subcommands.push(subcommand);
+        self
+    }
+
+    /// Check and build the spec, compiling the patterns of options.
+    pub fn build(self) -> Result<DynCommandSpec, DynCommandSpecError> {
+        let spec = self.spec;
+        for (i, option) in spec.options.iter().enumerate() {
+            if spec.options[..i]
+                .iter()
+                .any(|other| other.name == option.name)
+            {
+                return Err(DynCommandSpecError::DuplicateOption(option.name.clone()));
+            }
+            let value = match &option.value {
+                CommandOptionValueKind::Optional(value) => value.as_ref(),
+                value => value,
+            };
+            if matches!(
+                value,
+                CommandOptionValueKind::Optional(_) | CommandOptionValueKind::Multiple(_)
+            ) {
+                return Err(DynCommandSpecError::MultipleValues(option.name.clone()));
+            }
+            #[cfg(feature = "regex")]
+            if let Some(pattern) = &option.validation.pattern {
+                // Compile it now, so that the parse doesn't fail on it later.
+                if let Err(error) = pattern.regex() {
+                    return Err(DynCommandSpecError::InvalidPattern {
+                        option: option.name.clone(),
+                        reason: error.to_string(),
+                    });
+                }
+            }
+        }
+        let names = spec
+            .exclusive
+            .iter()
+            .flatten()
+            .chain(spec.options.iter().flat_map(|option| &option.requires));
+        for name in names {
+            if !spec.options.iter().any(|option| &option.name == name) {
+                return Err(DynCommandSpecError::UnknownOption(name.clone()));
+            }
+        }
+        for (i, subcommand) in spec.subcommands.iter().enumerate() {
+            let names = std::iter::once(&subcommand.name).chain(&subcommand.aliases);
+            for name in names {
+                if spec.subcommands[..i]
+                    .iter()
+                    .any(|other| other.selected_by(name))
+                {
+                    return Err(DynCommandSpecError::DuplicateSubcommand(name.clone()));
+                }
+            }
+        }
+        Ok(spec)
+    }
+}
+
+/// Convert the spec of derived command, so that it can be in one tree with the commands defined at runtime.
+///
+/// The commands with permissions, cooldown or confirmation are refused, as [`DynCommandSpec`] cannot keep them.
+/// The validation functions which are not in [`CommandSpec`] are not taken,
+/// and taking the rest is dropped, which only matters for lexing the raw message.
+impl TryFrom<&CommandSpec> for DynCommandSpec {
+    type Error = DynCommandSpecError;
+
+    fn try_from(spec: &CommandSpec) -> Result<Self, Self::Error> {
+        let requirement = if spec.permissions != CommandPermissions::default()
+            || spec.self_execution.permissions != CommandPermissions::default()
+        {
+            Some("permissions")
+        } else if spec.cooldown.is_some() || spec.self_execution.cooldown.is_some() {
+            Some("cooldown")
+        } else if spec.confirm.is_some() || spec.self_execution.confirm.is_some() {
+            Some("confirmation")
+        } else {
+            None
+        };
+        if let Some(requirement) = requirement {
+            return Err(DynCommandSpecError::UnsupportedRequirement {
+                command: spec.name.to_string(),
+                requirement,
+            });
+        }
+        let mut builder = DynCommandSpec::builder(spec.name).description(spec.description);
+        for alias in &spec.aliases {
+            builder = builder.alias(*alias);
+        }
+        for option in &spec.options {
+            builder = builder.option(DynCommandOption::try_from(option)?);
+        }
+        for group in &spec.exclusive {
+            builder = builder.exclusive(group.iter().copied());
+        }
+        for subcommand in &spec.subcommands {
+            builder = builder.subcommand(DynCommandSpec::try_from(subcommand)?);
+        }
+        let mut dyn_spec = builder.build()?;
+        // Keep the derived positions, which may not follow the order of options.
+        for (dyn_option, option) in dyn_spec.options.iter_mut().zip(&spec.options) {
+            dyn_option.position = option.position;
+        }
+        Ok(dyn_spec)
+    }
+}
+
+impl TryFrom<&CommandOption> for DynCommandOption {
+    type Error = DynCommandSpecError;
+
+    fn try_from(option: &CommandOption) -> Result<Self, Self::Error> {
+        if option.variadic {
+            return Err(DynCommandSpecError::MultipleValues(option.name.to_string()));
+        }
+        Ok(DynCommandOption {
+            name: option.name.to_string(),
+            position: option.position,
+            description: option.description.to_string(),
+            value: option.value.clone(),
+            validation: option.validation.clone(),
+            requires: option
+                .requires
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        })
+    }
+}
+
+/// The spec cannot be built into [`DynCommandSpec`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum DynCommandSpecError {
+    /// Options have the same name.
+    DuplicateOption(String),
+
+    /// Subcommands are selected by the same name.
+    DuplicateSubcommand(String),
+
+    /// The option referred by exclusive group or requirement doesn't exist.
+    UnknownOption(String),
+
+    /// The option takes multiple values, which [`ParsedInvocation`](`super::ParsedInvocation`) cannot hold.
+    MultipleValues(String),
+
+    /// The derived command has the requirement like permissions, which [`DynCommandSpec`] cannot keep.
+    UnsupportedRequirement {
+        /// The name of command
+        command: String,
+
+        /// The requirement, one of `"permissions"`, `"cooldown"` and `"confirmation"`
+        requirement: &'static str,
+    },
+
+    /// The pattern of option doesn't compile.
+    #[cfg(feature = "regex")]
+    InvalidPattern {
+        /// The name of option
+        option: String,
+
+        /// Why the pattern doesn't compile
+        reason: String,
+    },
+}
+
+impl fmt::Display for DynCommandSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynCommandSpecError::DuplicateOption(name) => write!(f, "Duplicate option: {}", name),
+            DynCommandSpecError::DuplicateSubcommand(name) => {
+                write!(f, "Duplicate subcommand: {}", name)
+            }
+            DynCommandSpecError::UnknownOption(name) => write!(f, "Unknown option: {}", name),
+            DynCommandSpecError::MultipleValues(name) => {
+                write!(f, "Option takes multiple values: {}", name)
+            }
+            DynCommandSpecError::UnsupportedRequirement {
+                command,
+                requirement,
+            } => write!(f, "Command {} has unsupported {}", command, requirement),
+            #[cfg(feature = "regex")]
+            DynCommandSpecError::InvalidPattern { option, reason } => {
+                write!(f, "Invalid pattern of option {}: {}", option, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DynCommandSpecError {}
@@ -9,6 +9,27 @@ pub enum CommandParseError<'a> {
     /// There are missing arguments
     MissingArguments(Vec<&'a str>),
 
+    /// The arguments which cannot be given together are given.
+    ConflictingArguments(Vec<&'a str>),
+
+    /// The argument is given without the argument it requires.
+    MissingRequiredArgument {
+        /// The name of argument given.
+        argument: &'a str,
+
+        /// The name of argument required.
+        requires: &'a str,
+    },
+
+    /// The argument value is rejected by its validation.
+    ValidationFailed {
+        /// The name of argument rejected.
+        argument: &'a str,
+
+        /// The reason why the value is rejected.
+        reason: String,
+    },
+
     /// The command fragments are incomplete for parsing.
     IncompleteCommand,
 
@@ -33,6 +54,15 @@ impl fmt::Display for CommandParseError<'_> {
             CommandParseError::MissingArguments(missing) => {
                 write!(f, "Missing arguments: {}", missing.join(", "))
             }
+            CommandParseError::ConflictingArguments(conflicting) => {
+                write!(f, "Conflicting arguments: {}", conflicting.join(", "))
+            }
+            CommandParseError::MissingRequiredArgument { argument, requires } => {
+                write!(f, "Argument {} requires {}", argument, requires)
+            }
+            CommandParseError::ValidationFailed { argument, reason } => {
+                write!(f, "Invalid argument {}: {}", argument, reason)
+            }
             CommandParseError::IncompleteCommand => write!(f, "Incomplete command"),
             CommandParseError::ExecuteTooEarly => write!(f, "Execute too early"),
             #[cfg(feature = "lex")]
@@ -0,0 +1,48 @@
+/// Split the command string into multiple commands by separators appearing outside quotes.
+///
+/// Separators are tried in the order given, so put longer one first if they share a prefix.
+/// Like [`CommandLexer`](`super::CommandLexer`), a quote only opens at the start of a token.
+/// Blank commands, like one after a trailing separator, are skipped.
+pub fn split_commands<'a>(
+    source: &'a str,
+    separators: &'a [&'a str],
+) -> impl Iterator<Item = &'a str> + 'a {
+    let mut commands = Vec::new();
+    let mut iter = source.char_indices().peekable();
+    let mut command_first = 0;
+    let mut token_start = true;
+
+    while let Some((i, ch)) = iter.next() {
+        if let Some(separator) = separators
+            .iter()
+            .find(|separator| !separator.is_empty() && source[i..].starts_with(*separator))
+        {
+            commands.push(&source[command_first..i]);
+            command_first = i + separator.len();
+            while iter.next_if(|(j, _)| *j < command_first).is_some() {}
+            token_start = true;
+            continue;
+        }
+
+        match ch {
+            '"' | '\'' if token_start => {
+                let mut previous_backslash = false;
+                for (_, c) in iter.by_ref() {
+                    match c {
+                        c if c == ch && !previous_backslash => break,
+                        '\\' if !previous_backslash => previous_backslash = true,
+                        _ => previous_backslash = false,
+                    }
+                }
+                token_start = false;
+            }
+            '=' => token_start = true,
+            ch => token_start = ch.is_whitespace(),
+        }
+    }
+    commands.push(&source[command_first..]);
+
+    commands
+        .into_iter()
+        .filter(|command| !command.trim().is_empty())
+}
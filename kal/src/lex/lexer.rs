@@ -2,7 +2,7 @@
 
 use std::{fmt, iter::Peekable, str::CharIndices};
 
-use super::{CommandToken, RawStringPattern};
+use super::{numeric, CommandToken};
 
 /// An error that can appear while lexing a command.
 #[derive(Debug, PartialEq, Eq)]
@@ -43,10 +43,58 @@ impl fmt::Display for CommandLexError<'_> {
 
 impl std::error::Error for CommandLexError<'_> {}
 
+impl<'a> CommandLexError<'a> {
+    /// The position in the command where the error starts.
+    pub fn position(&self) -> usize {
+        match self {
+            CommandLexError::UnclosedQuote(pos, _)
+            | CommandLexError::NamedProhibitsWhitespace(pos, _)
+            | CommandLexError::NamedCannotContainNamed(pos, _) => *pos,
+        }
+    }
+
+    /// The part of the command the error is about.
+    pub fn source_part(&self) -> &'a str {
+        match self {
+            CommandLexError::UnclosedQuote(_, src_part)
+            | CommandLexError::NamedProhibitsWhitespace(_, src_part)
+            | CommandLexError::NamedCannotContainNamed(_, src_part) => src_part,
+        }
+    }
+
+    fn with_source_part(self, src_part: &'a str) -> Self {
+        match self {
+            CommandLexError::UnclosedQuote(pos, _) => CommandLexError::UnclosedQuote(pos, src_part),
+            CommandLexError::NamedProhibitsWhitespace(pos, _) => {
+                CommandLexError::NamedProhibitsWhitespace(pos, src_part)
+            }
+            CommandLexError::NamedCannotContainNamed(pos, _) => {
+                CommandLexError::NamedCannotContainNamed(pos, src_part)
+            }
+        }
+    }
+}
+
+/// Options for [`CommandLexer`] enabling syntaxes which are ambiguous in some commands.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommandLexerOptions {
+    /// Recognize comma as a digit separator like `1,000`.
+    pub comma_digit_separator: bool,
+
+    /// Recognize `inf`, `infinity` and `nan` as floats.
+    pub special_floats: bool,
+
+    /// Keep lexing after an error instead of stopping.
+    /// The lexer resynchronizes at the next whitespace after the erroneous part,
+    /// and the error only holds that part of the command.
+    pub recover: bool,
+}
+
 /// A lexer transforming command string into sequence of [`CommandToken`]/
 pub struct CommandLexer<'a> {
     source: &'a str,
     iter: Peekable<CharIndices<'a>>,
+    options: CommandLexerOptions,
 
     in_named: bool,
     failed: bool,
@@ -55,9 +103,15 @@ pub struct CommandLexer<'a> {
 impl<'a> CommandLexer<'a> {
     /// Create a new `CommandLexer` instance from a command string.
     pub fn new(source: &'a str) -> Self {
+        Self::with_options(source, CommandLexerOptions::default())
+    }
+
+    /// Create a new `CommandLexer` instance from a command string with options.
+    pub fn with_options(source: &'a str, options: CommandLexerOptions) -> Self {
         CommandLexer {
             source,
             iter: source.char_indices().peekable(),
+            options,
             in_named: false,
             failed: false,
         }
@@ -84,29 +138,11 @@ impl<'a> CommandLexer<'a> {
         let (first, ch) = self.iter.next()?;
         let mut src = String::new();
 
-        let src_first = match self.iter.next() {
-            None => {
-                return Some(Err(CommandLexError::UnclosedQuote(
-                    first,
-                    &self.source[first..],
-                )))
-            }
-            Some((last, c)) if c == ch => {
-                return Some(Ok(CommandToken::QuotedString(
-                    &self.source[first..=last],
-                    "".to_string(),
-                    &self.source[last..=self
-                        .iter
-                        .peek()
-                        .map(|(next_i, _)| *next_i)
-                        .unwrap_or(self.source.len())],
-                )))
-            }
-            Some((src_first, ch)) => {
-                src.push(ch);
-                src_first
-            }
-        };
+        let src_first = self
+            .iter
+            .peek()
+            .map(|(src_first, _)| *src_first)
+            .unwrap_or(self.source.len());
 
         let mut previous_backslash = false;
         let src_last = loop {
@@ -159,8 +195,6 @@ impl<'a> CommandLexer<'a> {
         let (first, _) = self.iter.peek()?;
         let first = *first;
 
-        let mut is_numeric = true;
-        let mut met_float_dot = false;
         let last = loop {
             match self.iter.peek() {
                 Some((i, ch)) if ch.is_whitespace() => break *i,
@@ -183,7 +217,7 @@ impl<'a> CommandLexer<'a> {
                             }
                             self.iter.next();
                             self.in_named = true;
-                            let token = match self.next() {
+                            let token = match self.next_token() {
                                 Some(Ok(CommandToken::Whitespace(_))) | None => {
                                     return Some(Err(CommandLexError::NamedProhibitsWhitespace(
                                         first,
@@ -202,27 +236,7 @@ impl<'a> CommandLexer<'a> {
                             self.in_named = false;
                             return Some(Ok(CommandToken::Named(name, Box::new(token))));
                         }
-                        '0'..='9' => {
-                            self.iter.next();
-                        }
-                        '.' => {
-                            if met_float_dot {
-                                is_numeric = false;
-                            } else {
-                                met_float_dot = true;
-                            }
-                            self.iter.next();
-                        }
-                        '-' | '+' => {
-                            if first != i {
-                                is_numeric = false;
-                                met_float_dot = false;
-                            }
-                            self.iter.next();
-                        }
                         _ => {
-                            is_numeric = false;
-                            met_float_dot = false;
                             self.iter.next();
                         }
                     }
@@ -231,16 +245,40 @@ impl<'a> CommandLexer<'a> {
             }
         };
 
-        let pattern = match (is_numeric, met_float_dot) {
-            (false, _) => RawStringPattern::Unrecognized,
-            (true, true) => RawStringPattern::Float,
-            (true, false) => RawStringPattern::Integer,
-        };
+        let value = &self.source[first..last];
+        let pattern = numeric::recognize(value, &self.options);
 
-        Some(Ok(CommandToken::RawString(
-            &self.source[first..last],
-            pattern,
-        )))
+        Some(Ok(CommandToken::RawString(value, pattern)))
+    }
+}
+
+impl<'a> CommandLexer<'a> {
+    fn next_token(&mut self) -> Option<Result<CommandToken<'a>, CommandLexError<'a>>> {
+        let (_, ch) = self.iter.peek()?;
+
+        match ch {
+            ch if ch.is_whitespace() => self.next_whitespace(),
+            '"' | '\'' => self.next_quote(),
+            _ => self.next_raw_string_or_named(),
+        }
+    }
+
+    fn recover(&mut self, error: CommandLexError<'a>) -> CommandLexError<'a> {
+        let first = error.position();
+        self.in_named = false;
+        self.iter = self.source.char_indices().peekable();
+        while self.iter.next_if(|(i, _)| *i < first).is_some() {}
+
+        let last = loop {
+            match self.iter.peek() {
+                Some((i, ch)) if ch.is_whitespace() => break *i,
+                Some(_) => {
+                    self.iter.next();
+                }
+                None => break self.source.len(),
+            }
+        };
+        error.with_source_part(&self.source[first..last])
     }
 }
 
@@ -252,18 +290,34 @@ impl<'a> Iterator for CommandLexer<'a> {
             return None;
         }
 
-        let (_, ch) = self.iter.peek()?;
+        match self.next_token()? {
+            Err(error) if self.options.recover => Some(Err(self.recover(error))),
+            Err(error) => {
+                self.failed = true;
+                Some(Err(error))
+            }
+            token => Some(token),
+        }
+    }
+}
 
-        let res = match ch {
-            ch if ch.is_whitespace() => self.next_whitespace(),
-            '"' | '\'' => self.next_quote(),
-            _ => self.next_raw_string_or_named(),
-        };
+/// Lex the whole command in recovering mode, splitting tokens and every error found.
+pub fn lex_all(source: &str) -> (Vec<CommandToken<'_>>, Vec<CommandLexError<'_>>) {
+    let lexer = CommandLexer::with_options(
+        source,
+        CommandLexerOptions {
+            recover: true,
+            ..CommandLexerOptions::default()
+        },
+    );
 
-        if res.as_ref().map(|res| res.is_err()).unwrap_or(false) {
-            self.failed = true;
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+    for res in lexer {
+        match res {
+            Ok(token) => tokens.push(token),
+            Err(error) => errors.push(error),
         }
-
-        res
     }
+    (tokens, errors)
 }
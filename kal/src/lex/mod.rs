@@ -11,6 +11,13 @@
 //! - Quoted string
 //! - Named raw string or quoted string
 //!
+//! Raw strings are analyzed as integers, floats, scientific notation, hexadecimal/octal/binary
+//! and digit separated (`1_000`) numbers. Ambiguous syntaxes like `1,000` and `inf`/`nan`
+//! should be enabled with [`CommandLexerOptions`].
+//!
+//! The lexer stops at the first error by default. Enable `recover` in [`CommandLexerOptions`]
+//! or use [`lex_all`] to collect every error in the command.
+//!
 //! See belo example for sure.
 //! ```rust
 //! # use kal::lex::{CommandLexer, CommandToken, RawStringPattern};
@@ -104,6 +111,8 @@
 //! Enable both `"lex"` and `"derive"` feature flags (later one is enabled by default),
 //! and use `#[derive(TransformHintProvider)]` on your item.
 //! It will take your `#[argument]` attributes for generating hints.
+//! For a command without the derive, [`TransformHint`] can also be made from its [`CommandSpec`](`crate::CommandSpec`)
+//! like `TransformHint::from(&Hello::spec())`.
 //!
 //! ```rust
 //! # use kal::Command;
@@ -125,25 +134,86 @@
 //!     TransformHint::Select(HashMap::from_iter([
 //!         (
 //!             "world",
-//!             TransformHint::Execute(vec![TransformHintPart::String]),
+//!             TransformHint::Execute(vec![("argument", TransformHintPart::String)]),
 //!         ),
 //!         (
 //!             "sekai",
-//!             TransformHint::Execute(vec![TransformHintPart::StringGreedy]),
+//!             TransformHint::Execute(vec![("argument", TransformHintPart::StringGreedy)]),
 //!         ),
 //!    ]))
 //! );
 //! ```
+//!
+//! `#[derive(CommandGroup)]` implements [`TransformHintProvider`] by itself,
+//! selecting each variant's hint by its name and aliases.
+//! Each variant's hint is made from its [`CommandSpec`](`crate::CommandSpec`).
+//! To use the command's own [`TransformHintProvider`] instead, mark the variant with `#[command(hint_provider)]`
+//! (`#[hint_provider]` in [`command_group!`](`crate::command_group!`)).
+//!
+//! ```rust
+//! # use kal::{Command, CommandGroup};
+//! # use kal::lex::{TransformHint, TransformHintProvider};
+//! # use std::collections::HashMap;
+//! #[derive(Command, TransformHintProvider)]
+//! struct A;
+//!
+//! #[derive(Command)]
+//! struct B;
+//!
+//! #[derive(CommandGroup)]
+//! enum Root {
+//!     #[command(hint_provider)]
+//!     A(A),
+//!     B(B),
+//! }
+//!
+//! assert_eq!(
+//!     Root::hint(),
+//!     TransformHint::Select(HashMap::from_iter([
+//!         ("a", TransformHint::Execute(vec![])),
+//!         ("b", TransformHint::Execute(vec![])),
+//!     ]))
+//! );
+//! ```
+//!
+//! ## Render
+//!
+//! [`render`] is the inverse of lex and transform.
+//! It renders [`CommandFragment`](`crate::CommandFragment`) sequence, usually made by [`ToFragments::to_fragments`](`crate::ToFragments::to_fragments`),
+//! back into a command string quoting and escaping arguments as needed.
+//!
+//! ```rust
+//! # use kal::lex::{render, RenderStyle};
+//! # use kal::{CommandArgument, CommandArgumentValue, CommandFragment};
+//! let fragments = vec![
+//!     CommandFragment::Select("remind".to_string()),
+//!     CommandFragment::Execute(vec![
+//!         CommandArgument::Positioned(0, CommandArgumentValue::I64(5)),
+//!         CommandArgument::Positioned(1, CommandArgumentValue::String("buy milk".to_string())),
+//!     ]),
+//! ];
+//! let style = RenderStyle {
+//!     label_prefix: "/",
+//!     ..RenderStyle::default()
+//! };
+//! assert_eq!(render(&fragments, style), r#"/remind 5 "buy milk""#);
+//! ```
 #![cfg(feature = "lex")]
+pub use batch::split_commands;
+#[cfg(feature = "derive")]
 pub use kal_derive::TransformHintProvider;
 pub use label_strip_util::{remove_leading, remove_trailing};
-pub use lexer::{CommandLexError, CommandLexer};
+pub use lexer::{lex_all, CommandLexError, CommandLexer, CommandLexerOptions};
+pub use render::{render, RenderStyle};
 pub use token::{CommandToken, RawStringPattern};
 pub use transform_hint::{TransformHint, TransformHintPart, TransformHintProvider};
 pub use transformer::{TokenTransformError, TokenTransformer};
 
+mod batch;
 mod label_strip_util;
 mod lexer;
+mod numeric;
+pub(crate) mod render;
 mod token;
 mod transform_hint;
 mod transformer;
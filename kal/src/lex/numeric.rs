@@ -0,0 +1,135 @@
+//! Recognize and convert numeric literals appearing in raw strings.
+
+use super::{CommandLexerOptions, RawStringPattern};
+
+/// Analyze which numeric pattern the raw string matches.
+pub(crate) fn recognize(s: &str, options: &CommandLexerOptions) -> RawStringPattern {
+    let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+
+    if options.special_floats {
+        if unsigned.eq_ignore_ascii_case("inf") || unsigned.eq_ignore_ascii_case("infinity") {
+            return RawStringPattern::Infinity;
+        }
+        if unsigned.eq_ignore_ascii_case("nan") {
+            return RawStringPattern::NaN;
+        }
+    }
+
+    let radix = [
+        ("0x", 16, RawStringPattern::Hexadecimal),
+        ("0o", 8, RawStringPattern::Octal),
+        ("0b", 2, RawStringPattern::Binary),
+    ];
+    for (prefix, radix, pattern) in radix {
+        let digits = match unsigned.get(..2) {
+            Some(head) if head.eq_ignore_ascii_case(prefix) => &unsigned[2..],
+            _ => continue,
+        };
+        return if is_digits(digits, radix) {
+            pattern
+        } else {
+            RawStringPattern::Unrecognized
+        };
+    }
+
+    recognize_decimal(unsigned, options)
+}
+
+fn recognize_decimal(s: &str, options: &CommandLexerOptions) -> RawStringPattern {
+    let (mantissa, exponent) = match s.find(['e', 'E']) {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+    let (integer, fraction) = match mantissa.find('.') {
+        Some(i) => (&mantissa[..i], Some(&mantissa[i + 1..])),
+        None => (mantissa, None),
+    };
+
+    let integer_ok = integer.is_empty()
+        || is_digits(integer, 10)
+        || (options.comma_digit_separator && is_comma_grouped(integer));
+    let fraction_ok = fraction
+        .map(|fraction| fraction.is_empty() || is_digits(fraction, 10))
+        .unwrap_or(true);
+    let has_digit = integer
+        .chars()
+        .chain(fraction.unwrap_or("").chars())
+        .any(|ch| ch.is_ascii_digit());
+    if !integer_ok || !fraction_ok || !has_digit {
+        return RawStringPattern::Unrecognized;
+    }
+
+    if let Some(exponent) = exponent {
+        let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        return if is_digits(exponent, 10) {
+            RawStringPattern::Scientific
+        } else {
+            RawStringPattern::Unrecognized
+        };
+    }
+
+    let separated = mantissa.contains(['_', ',']);
+    match (fraction.is_some(), separated) {
+        (false, false) => RawStringPattern::Integer,
+        (false, true) => RawStringPattern::SeparatedInteger,
+        (true, false) => RawStringPattern::Float,
+        (true, true) => RawStringPattern::SeparatedFloat,
+    }
+}
+
+/// Whether the string is a non-empty sequence of digits, optionally separated by single underscores.
+fn is_digits(s: &str, radix: u32) -> bool {
+    if s.is_empty() || s.starts_with('_') || s.ends_with('_') || s.contains("__") {
+        return false;
+    }
+    s.chars().all(|ch| ch == '_' || ch.is_digit(radix))
+}
+
+/// Whether the string is a digit sequence grouped by commas in thousands like `1,000,000`.
+fn is_comma_grouped(s: &str) -> bool {
+    let mut groups = s.split(',');
+    let leading = groups.next().unwrap_or("");
+    let leading_ok =
+        (1..=3).contains(&leading.len()) && leading.chars().all(|ch| ch.is_ascii_digit());
+    let mut rest = groups.peekable();
+    leading_ok
+        && rest.peek().is_some()
+        && rest.all(|group| group.len() == 3 && group.chars().all(|ch| ch.is_ascii_digit()))
+}
+
+/// Convert the raw string into `i64` if the pattern represents an integer.
+pub(crate) fn parse_i64(s: &str, pattern: &RawStringPattern) -> Option<i64> {
+    let (negative, unsigned) = match s.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let (digits, radix) = match pattern {
+        RawStringPattern::Integer | RawStringPattern::SeparatedInteger => (unsigned, 10),
+        RawStringPattern::Hexadecimal => (&unsigned[2..], 16),
+        RawStringPattern::Octal => (&unsigned[2..], 8),
+        RawStringPattern::Binary => (&unsigned[2..], 2),
+        _ => return None,
+    };
+    let digits: String = digits
+        .chars()
+        .filter(|ch| !matches!(ch, '_' | ','))
+        .collect();
+    let magnitude = i128::from_str_radix(&digits, radix).ok()?;
+    i64::try_from(if negative { -magnitude } else { magnitude }).ok()
+}
+
+/// Convert the raw string into `f64` if the pattern represents any number.
+pub(crate) fn parse_f64(s: &str, pattern: &RawStringPattern) -> Option<f64> {
+    match pattern {
+        RawStringPattern::Unrecognized => None,
+        RawStringPattern::Hexadecimal | RawStringPattern::Octal | RawStringPattern::Binary => {
+            parse_i64(s, pattern).map(|i| i as f64)
+        }
+        _ => s
+            .chars()
+            .filter(|ch| !matches!(ch, '_' | ','))
+            .collect::<String>()
+            .parse()
+            .ok(),
+    }
+}
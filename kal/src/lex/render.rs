@@ -0,0 +1,152 @@
+//! Render command fragments back into a command string.
+
+use crate::{CommandArgument, CommandArgumentValue, CommandFragment};
+
+use super::{
+    transform_hint::{named_part, positioned_part},
+    TransformHint, TransformHintPart,
+};
+
+/// The style [`render`] writes a command string in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RenderStyle<'a> {
+    /// The string put before the first label, like `/` of `/hello world`.
+    pub label_prefix: &'a str,
+
+    /// The quote wrapping a string which cannot be written raw. It should be `"` or `'`.
+    pub quote: char,
+
+    /// The hint the rendered command will be transformed with.
+    /// Arguments hinted as [`TransformHintPart::StringGreedy`] are written raw
+    /// since the transformer takes the rest of the command as is.
+    pub hint: Option<&'a TransformHint>,
+}
+
+impl Default for RenderStyle<'_> {
+    fn default() -> Self {
+        RenderStyle {
+            label_prefix: "",
+            quote: '"',
+            hint: None,
+        }
+    }
+}
+
+/// Render [`CommandFragment`] sequence into a command string,
+/// which [`CommandLexer`](`super::CommandLexer`) and [`TokenTransformer`](`super::TokenTransformer`) transform back into the same fragments.
+/// Non-finite floats are written as `inf`, `-inf` and `nan`, which are read back as floats
+/// only with [`CommandLexerOptions::special_floats`](`super::CommandLexerOptions::special_floats`) enabled.
+pub fn render(fragments: &[CommandFragment], style: RenderStyle) -> String {
+    let mut result = String::new();
+    let mut hint = style.hint;
+
+    for fragment in fragments {
+        match fragment {
+            CommandFragment::Select(name) => {
+                if result.is_empty() {
+                    result.push_str(style.label_prefix);
+                } else {
+                    result.push(' ');
+                }
+                result.push_str(name);
+                hint = hint.and_then(|hint| match hint {
+                    TransformHint::Select(map) | TransformHint::SelectOrExecute(map, _) => {
+                        map.get(name.as_str())
+                    }
+                    TransformHint::Execute(_) => None,
+                });
+            }
+            CommandFragment::Execute(arguments) => {
+                let parts = match hint {
+                    Some(TransformHint::Execute(parts))
+                    | Some(TransformHint::SelectOrExecute(_, parts)) => parts.as_slice(),
+                    _ => &[],
+                };
+
+                let mut pos = 0;
+                for (i, argument) in arguments.iter().enumerate() {
+                    if !result.is_empty() {
+                        result.push(' ');
+                    }
+                    let (value, part) = match argument {
+                        CommandArgument::Named(name, value) => {
+                            result.push_str(name);
+                            result.push('=');
+                            // Named argument never takes the rest of the command.
+                            let part = named_part(parts, name)
+                                .filter(|part| **part != TransformHintPart::StringGreedy);
+                            (value, part)
+                        }
+                        CommandArgument::Positioned(_, value) => {
+                            pos += 1;
+                            (value, positioned_part(parts, pos - 1))
+                        }
+                    };
+                    // A leading raw string naming a subcommand would be taken as a selection.
+                    let force_quote = i == 0
+                        && match (hint, value) {
+                            (Some(TransformHint::Execute(_)), _) => false,
+                            (
+                                Some(TransformHint::SelectOrExecute(map, _)),
+                                CommandArgumentValue::String(s),
+                            ) => map.contains_key(s.as_str()),
+                            (Some(TransformHint::SelectOrExecute(..)), _) => false,
+                            _ => true,
+                        };
+                    render_value(&mut result, value, part, force_quote, style);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Render an argument value alone, quoting it if needed.
+pub(crate) fn render_argument_value(value: &CommandArgumentValue) -> String {
+    let mut result = String::new();
+    render_value(&mut result, value, None, false, RenderStyle::default());
+    result
+}
+
+fn render_value(
+    result: &mut String,
+    value: &CommandArgumentValue,
+    part: Option<&TransformHintPart>,
+    force_quote: bool,
+    style: RenderStyle,
+) {
+    match value {
+        CommandArgumentValue::I64(i) => result.push_str(&i.to_string()),
+        CommandArgumentValue::F64(f) if f.is_nan() => result.push_str("nan"),
+        CommandArgumentValue::F64(f) if f.is_infinite() => {
+            result.push_str(if f.is_sign_negative() { "-inf" } else { "inf" })
+        }
+        CommandArgumentValue::F64(f) => result.push_str(&f.to_string()),
+        CommandArgumentValue::String(s) => match part {
+            Some(TransformHintPart::StringGreedy) => result.push_str(s),
+            Some(TransformHintPart::Integer | TransformHintPart::Float) => {
+                push_quoted(result, s, style.quote)
+            }
+            _ if force_quote || needs_quote(s) => push_quoted(result, s, style.quote),
+            _ => result.push_str(s),
+        },
+    }
+}
+
+fn needs_quote(s: &str) -> bool {
+    s.is_empty()
+        || s.starts_with(['"', '\''])
+        || s.contains(|ch: char| ch.is_whitespace() || ch == '=')
+}
+
+fn push_quoted(result: &mut String, s: &str, quote: char) {
+    result.push(quote);
+    for ch in s.chars() {
+        if ch == quote || ch == '\\' {
+            result.push('\\');
+        }
+        result.push(ch);
+    }
+    result.push(quote);
+}
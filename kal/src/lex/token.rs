@@ -10,6 +10,30 @@ pub enum RawStringPattern {
 
     /// Simillar to integer, but there's one dot between digits.
     Float,
+
+    /// Integer with digit separators like `1_000` or `1,000`.
+    SeparatedInteger,
+
+    /// Float with digit separators like `1_000.5`.
+    SeparatedFloat,
+
+    /// Float in scientific notation like `1e5` or `2.5E-3`.
+    Scientific,
+
+    /// Integer in hexadecimal notation like `0xff`.
+    Hexadecimal,
+
+    /// Integer in octal notation like `0o17`.
+    Octal,
+
+    /// Integer in binary notation like `0b1010`.
+    Binary,
+
+    /// `inf` or `infinity` with optional sign, only if enabled by [`CommandLexerOptions`](`super::CommandLexerOptions`).
+    Infinity,
+
+    /// `nan`, only if enabled by [`CommandLexerOptions`](`super::CommandLexerOptions`).
+    NaN,
 }
 
 /// The token made out of command string.
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::CommandOptionValueKind;
+use crate::{CommandOption, CommandOptionValueKind, CommandSpec};
 
 /// The kind of [`TransformHintPart`].
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -16,6 +16,9 @@ pub enum TransformHintPart {
 
     /// Make transformer to eat all tokens until the end of the input and make as a single string.
     StringGreedy,
+
+    /// Apply the inner hint to this and every following positioned argument.
+    Variadic(Box<TransformHintPart>),
 }
 
 impl TransformHintPart {
@@ -26,6 +29,41 @@ impl TransformHintPart {
             itself => itself,
         }
     }
+
+    /// Make itself applied to every following positioned argument.
+    pub fn make_variadic(self) -> Self {
+        TransformHintPart::Variadic(Box::new(self))
+    }
+
+    fn element(&self) -> &TransformHintPart {
+        match self {
+            TransformHintPart::Variadic(part) => part.element(),
+            part => part,
+        }
+    }
+}
+
+/// Find the hint part for the positioned argument, following the variadic part at the end.
+pub(crate) fn positioned_part<'a>(
+    parts: &'a [(&'static str, TransformHintPart)],
+    position: usize,
+) -> Option<&'a TransformHintPart> {
+    match (parts.get(position), parts.last()) {
+        (Some((_, part)), _) => Some(part.element()),
+        (None, Some((_, part @ TransformHintPart::Variadic(_)))) => Some(part.element()),
+        _ => None,
+    }
+}
+
+/// Find the hint part for the named argument.
+pub(crate) fn named_part<'a>(
+    parts: &'a [(&'static str, TransformHintPart)],
+    name: &str,
+) -> Option<&'a TransformHintPart> {
+    parts
+        .iter()
+        .find(|(part_name, _)| *part_name == name)
+        .map(|(_, part)| part.element())
 }
 
 impl From<CommandOptionValueKind> for TransformHintPart {
@@ -40,6 +78,19 @@ impl From<CommandOptionValueKind> for TransformHintPart {
     }
 }
 
+impl From<&CommandOption> for TransformHintPart {
+    fn from(option: &CommandOption) -> Self {
+        let part = TransformHintPart::from(option.value.clone());
+        if option.take_rest {
+            part.make_greedy()
+        } else if option.variadic {
+            part.make_variadic()
+        } else {
+            part
+        }
+    }
+}
+
 /// The hint for [`TokenTransformer`](`super::TokenTransformer`) to produce [`CommandFragment`](`crate::CommandFragment`) well.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TransformHint {
@@ -47,10 +98,38 @@ pub enum TransformHint {
     Select(HashMap<&'static str, TransformHint>),
 
     /// It should execute command with arguments format hint specified.
-    Execute(Vec<TransformHintPart>),
+    /// Each hint is paired with the argument name and applied by the name for named arguments, by the position for positioned arguments.
+    Execute(Vec<(&'static str, TransformHintPart)>),
 
     /// It should select a command specified in a map or execute the command with arguments format hint specified.
-    SelectOrExecute(HashMap<&'static str, TransformHint>, Vec<TransformHintPart>),
+    SelectOrExecute(
+        HashMap<&'static str, TransformHint>,
+        Vec<(&'static str, TransformHintPart)>,
+    ),
+}
+
+impl From<&CommandSpec> for TransformHint {
+    fn from(spec: &CommandSpec) -> Self {
+        let parts: Vec<_> = spec
+            .options
+            .iter()
+            .map(|option| (option.name, TransformHintPart::from(option)))
+            .collect();
+        let mut subcommands = HashMap::new();
+        for subcommand in &spec.subcommands {
+            let hint = TransformHint::from(subcommand);
+            for alias in &subcommand.aliases {
+                subcommands.insert(*alias, hint.clone());
+            }
+            subcommands.insert(subcommand.name, hint);
+        }
+
+        match (parts.is_empty(), subcommands.is_empty()) {
+            (_, true) => TransformHint::Execute(parts),
+            (true, false) => TransformHint::Select(subcommands),
+            (false, false) => TransformHint::SelectOrExecute(subcommands, parts),
+        }
+    }
 }
 
 /// A type can provide a [`TransformHint`].
@@ -2,7 +2,11 @@ use core::fmt;
 
 use crate::{lex::TransformHintPart, CommandArgument, CommandArgumentValue, CommandFragment};
 
-use super::{CommandLexError, CommandToken, RawStringPattern, TransformHint};
+use super::{
+    numeric, split_commands,
+    transform_hint::{named_part, positioned_part},
+    CommandLexError, CommandLexer, CommandToken, TransformHint,
+};
 
 /// An error that can appear while transform the token produced into a command fragment.
 #[derive(Debug, PartialEq, Eq)]
@@ -70,6 +74,21 @@ where
         }
     }
 
+    pub(crate) fn new(label_stripper: Option<F>, hint: TransformHint) -> Self {
+        TokenTransformer {
+            label_stripper,
+            hint,
+        }
+    }
+
+    pub(crate) fn set_label_stripper(&mut self, label_stripper: F) {
+        self.label_stripper = Some(label_stripper);
+    }
+
+    pub(crate) fn hint_mut(&mut self) -> &mut TransformHint {
+        &mut self.hint
+    }
+
     /// Transform `Iterator` yielding [`CommandToken`] into an `Iterator` yielding [`CommandFragment`].
     pub fn transform<'a, 'b: 'a>(
         &'b self,
@@ -83,10 +102,22 @@ where
                 TokenTransformerHandleState::Subcommand
             },
             tokens,
-            hint: Some(self.hint.clone()),
+            hint: Some(&self.hint),
             named_produced: false,
         }
     }
+
+    /// Split the command string by separators like `;`, `&&` or `\n` with [`split_commands`],
+    /// then transform each command into a group of [`CommandFragment`].
+    /// An error only fails the command it appears in, and its position is relative to that command.
+    pub fn transform_batch<'a, 'b: 'a>(
+        &'b self,
+        source: &'a str,
+        separators: &'a [&'a str],
+    ) -> impl Iterator<Item = Result<Vec<CommandFragment>, TokenTransformError<'a>>> + 'a {
+        split_commands(source, separators)
+            .map(move |command| self.transform(CommandLexer::new(command)).collect())
+    }
 }
 
 enum TokenTransformerHandleState {
@@ -102,7 +133,7 @@ where
     transformer: &'a TokenTransformer<F>,
     state: TokenTransformerHandleState,
     tokens: I,
-    hint: Option<TransformHint>,
+    hint: Option<&'a TransformHint>,
     named_produced: bool,
 }
 
@@ -125,8 +156,9 @@ where
                         .unwrap_or(Ok(label));
                     break match transformed {
                         Ok(transformed) => {
-                            self.hint = self.hint.as_ref().and_then(|hint| match hint {
-                                TransformHint::Select(map) => map.get(transformed).cloned(),
+                            self.hint = self.hint.and_then(|hint| match hint {
+                                TransformHint::Select(map)
+                                | TransformHint::SelectOrExecute(map, _) => map.get(transformed),
                                 _ => None,
                             });
                             Some(Ok(CommandFragment::Select(transformed.to_string())))
@@ -153,9 +185,17 @@ where
                 Some(Ok(CommandToken::Whitespace(_))) => {
                     continue;
                 }
-                Some(Ok(CommandToken::RawString(subcommand, _))) => {
-                    self.hint = self.hint.as_ref().and_then(|hint| match hint {
-                        TransformHint::Select(map) => map.get(subcommand).cloned(),
+                Some(Ok(CommandToken::RawString(subcommand, pat))) => {
+                    if let Some(TransformHint::SelectOrExecute(map, _)) = self.hint {
+                        if !map.contains_key(subcommand) {
+                            let current = Some(Ok(CommandToken::RawString(subcommand, pat)));
+                            break self.next_args(current);
+                        }
+                    }
+                    self.hint = self.hint.and_then(|hint| match hint {
+                        TransformHint::Select(map) | TransformHint::SelectOrExecute(map, _) => {
+                            map.get(subcommand)
+                        }
                         _ => None,
                     });
                     break Some(Ok(CommandFragment::Select(subcommand.to_string())));
@@ -175,24 +215,20 @@ where
         &mut self,
         mut current: Option<Result<CommandToken<'a>, CommandLexError<'a>>>,
     ) -> Option<Result<CommandFragment, TokenTransformError<'a>>> {
-        let hint_seq = self
-            .hint
-            .clone()
-            .and_then(|hint| match hint {
-                TransformHint::Execute(seq) => Some(seq),
-                _ => None,
-            })
-            .unwrap_or_default();
-        let mut hint_seq = hint_seq.iter();
+        let hint_parts = match self.hint {
+            Some(TransformHint::Execute(parts) | TransformHint::SelectOrExecute(_, parts)) => {
+                parts.as_slice()
+            }
+            _ => &[],
+        };
+        let positioned_hint = |pos: usize| positioned_part(hint_parts, pos);
+        let named_hint = |name: &str| named_part(hint_parts, name);
         let mut args = Vec::new();
         let mut pos = 0;
         let mut greedy_string = None;
 
-        let mut hint = hint_seq.next();
         loop {
-            let is_greedy = hint
-                .map(|hint| matches!(hint, TransformHintPart::StringGreedy))
-                .unwrap_or(false);
+            let is_greedy = matches!(positioned_hint(pos), Some(TransformHintPart::StringGreedy));
 
             if is_greedy {
                 let greedy = greedy_string.get_or_insert("".to_string());
@@ -232,35 +268,39 @@ where
                         fn into_command_argument_value(
                             hint_part: Option<&TransformHintPart>,
                             token: CommandToken,
-                        ) -> Option<(Option<String>, CommandArgumentValue)>
-                        {
+                        ) -> Option<CommandArgumentValue> {
                             match token {
-                                CommandToken::Whitespace(_) => (None),
+                                CommandToken::Whitespace(_) | CommandToken::Named(..) => None,
                                 CommandToken::RawString(value, pat) => {
-                                    let value = match (hint_part, pat) {
-                                        (
-                                            Some(TransformHintPart::Float),
-                                            RawStringPattern::Float | RawStringPattern::Integer,
-                                        ) => CommandArgumentValue::F64(value.parse().unwrap()),
-                                        (
-                                            Some(TransformHintPart::Integer),
-                                            RawStringPattern::Integer,
-                                        ) => CommandArgumentValue::I64(value.parse().unwrap()),
-                                        _ => CommandArgumentValue::String(value.to_string()),
+                                    let number = match hint_part {
+                                        Some(TransformHintPart::Float) => {
+                                            numeric::parse_f64(value, &pat)
+                                                .map(CommandArgumentValue::F64)
+                                        }
+                                        Some(TransformHintPart::Integer) => {
+                                            numeric::parse_i64(value, &pat)
+                                                .map(CommandArgumentValue::I64)
+                                        }
+                                        _ => None,
                                     };
-                                    Some((None, value))
+                                    Some(number.unwrap_or_else(|| {
+                                        CommandArgumentValue::String(value.to_string())
+                                    }))
                                 }
                                 CommandToken::QuotedString(_, value, _) => {
-                                    Some((None, CommandArgumentValue::String(value)))
+                                    Some(CommandArgumentValue::String(value))
                                 }
-                                CommandToken::Named(name, value) => Some((
-                                    Some(name.to_string()),
-                                    into_command_argument_value(hint_part, *value).unwrap().1,
-                                )),
                             }
                         }
 
-                        let value = into_command_argument_value(hint, token);
+                        let value = match token {
+                            CommandToken::Named(name, value) => {
+                                into_command_argument_value(named_hint(name), *value)
+                                    .map(|value| (Some(name.to_string()), value))
+                            }
+                            token => into_command_argument_value(positioned_hint(pos), token)
+                                .map(|value| (None, value)),
+                        };
 
                         if let Some((name, value)) = value {
                             let is_named = name.is_some();
@@ -280,7 +320,6 @@ where
                                 }
                                 pos += 1;
                             }
-                            hint = hint_seq.next();
                         }
                     }
                     Some(Err(e)) => return Some(Err(TokenTransformError::from(e))),
@@ -8,19 +8,49 @@
 #![cfg_attr(not(debug_assertions), deny(missing_docs))]
 #![cfg_attr(debug_assertions, warn(missing_docs))]
 
-pub use command::Command;
+pub use arguments::Arguments;
+pub use command::{Command, ToFragments};
 pub use command_fragment::{
     CommaSeparated, CommandArgument, CommandArgumentValue, CommandArgumentValueType,
-    CommandFragment, SpaceSeparated, TryFromArgumentValue, TryFromArgumentValueError,
+    CommandFragment, SpaceSeparated, ToArgumentValue, TryFromArgumentValue,
+    TryFromArgumentValueError,
+};
+#[cfg(feature = "regex")]
+pub use command_spec::CommandOptionPattern;
+pub use command_spec::{
+    CommandOption, CommandOptionValidation, CommandOptionValueKind, CommandOptionValueTy,
+    CommandSelfExecution, CommandSpec,
+};
+#[cfg(feature = "lex")]
+pub use complete::{complete, complete_async, Completion, CompletionKind};
+pub use completion_provider::{CompletionContext, CompletionProvider};
+pub use confirmation::{
+    Confirmation, ConfirmationCaller, ConfirmationTracker, PendingConfirmation, CONFIRM_ARGUMENT,
+};
+pub use conversation::{Conversation, ConversationStep, Prompt};
+pub use cooldown::{
+    Clock, CommandCooldown, CooldownCaller, CooldownTracker, RetryAfter, SystemClock,
 };
-pub use command_spec::{CommandOption, CommandOptionValueKind, CommandOptionValueTy, CommandSpec};
 pub use error::CommandParseError;
-pub use kal_derive::Command;
+#[cfg(feature = "derive")]
+pub use kal_derive::{Arguments, Command, CommandGroup};
+pub use permission::{CommandPermissions, PermissionChecker};
+pub use plugin::{CommandAdapter, CommandPlugin, PluginRegistry};
 
+mod arguments;
 mod command;
 mod command_fragment;
 mod command_group;
 mod command_spec;
+mod complete;
+mod completion_provider;
+mod confirmation;
+mod conversation;
+mod cooldown;
 mod error;
+mod permission;
+mod plugin;
 
+pub mod dispatch;
+pub mod dyn_command;
 pub mod lex;
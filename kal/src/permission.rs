@@ -0,0 +1,65 @@
+use crate::CommandSpec;
+
+/// The permissions required to run a command
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommandPermissions {
+    /// The permissions every one of them is required
+    pub required: Vec<&'static str>,
+
+    /// Whether only the owner can run the command
+    pub owner_only: bool,
+}
+
+/// A principal running commands, like a user in the channel the command is sent.
+pub trait PermissionChecker {
+    /// Whether the principal is the owner.
+    fn is_owner(&self) -> bool;
+
+    /// Whether the principal has the permission.
+    fn has_permission(&self, permission: &str) -> bool;
+
+    /// Whether the principal satisfies the permissions.
+    fn is_permitted(&self, permissions: &CommandPermissions) -> bool {
+        (!permissions.owner_only || self.is_owner())
+            && permissions
+                .required
+                .iter()
+                .all(|permission| self.has_permission(permission))
+    }
+}
+
+impl CommandSpec {
+    /// Prune the spec tree to the commands the principal is permitted to run.
+    /// A subcommand is hidden together with its parent,
+    /// and a command only selecting subcommands is hidden when none of them is left.
+    /// A command the principal cannot execute itself loses its options, only selecting subcommands.
+    pub fn visible_to(&self, checker: &(impl PermissionChecker + ?Sized)) -> Option<CommandSpec> {
+        if !checker.is_permitted(&self.permissions) {
+            return None;
+        }
+        if !checker.is_permitted(&self.self_execution.permissions) {
+            if self.subcommands.is_empty() {
+                return None;
+            }
+            let spec = CommandSpec {
+                options: Vec::new(),
+                exclusive: Vec::new(),
+                self_execution: Default::default(),
+                ..self.clone()
+            };
+            return spec.visible_to(checker);
+        }
+        let subcommands: Vec<_> = self
+            .subcommands
+            .iter()
+            .filter_map(|subcommand| subcommand.visible_to(checker))
+            .collect();
+        if subcommands.is_empty() && !self.subcommands.is_empty() && self.options.is_empty() {
+            return None;
+        }
+        Some(CommandSpec {
+            subcommands,
+            ..self.clone()
+        })
+    }
+}
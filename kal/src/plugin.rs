@@ -0,0 +1,142 @@
+use std::any::Any;
+
+use crate::{Command, CommandFragment, CommandParseError, CommandPermissions, CommandSpec};
+
+/// The object-safe companion of [`Command`] to keep commands of different types together,
+/// parsing each into the same output type `O`.
+///
+/// [`CommandAdapter`] implements it for any [`Command`].
+pub trait CommandPlugin<O = Box<dyn Any + Send + Sync>>: Send + Sync {
+    /// The name of command, like [`Command::NAME`]
+    fn name(&self) -> &'static str;
+
+    /// The spec of command, like [`Command::spec_ref`]
+    fn spec(&self) -> &'static CommandSpec;
+
+    /// Parse the command from [`CommandFragment`] sequence, like [`Command::parse`].
+    fn parse<'a>(&self, fragments: &'a [CommandFragment]) -> Result<O, CommandParseError<'a>>;
+}
+
+/// [`CommandPlugin`] for the command `T`, converting it into `O` after parsed.
+pub struct CommandAdapter<T, O = Box<dyn Any + Send + Sync>> {
+    map: fn(T) -> O,
+}
+
+impl<T: Command + Send + Sync + 'static> CommandAdapter<T> {
+    /// Make a new adapter parsing into [`Box<dyn Any>`](`Any`), which can be downcast to `T`.
+    pub fn new() -> Self {
+        CommandAdapter {
+            map: |command| Box::new(command),
+        }
+    }
+}
+
+impl<T: Command + Send + Sync + 'static> Default for CommandAdapter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Command, O> CommandAdapter<T, O> {
+    /// Make a new adapter converting the command with `map`, like the variant of your enum.
+    pub fn map(map: fn(T) -> O) -> Self {
+        CommandAdapter { map }
+    }
+}
+
+impl<T: Command + 'static, O> CommandPlugin<O> for CommandAdapter<T, O> {
+    fn name(&self) -> &'static str {
+        T::NAME
+    }
+
+    fn spec(&self) -> &'static CommandSpec {
+        T::spec_ref()
+    }
+
+    fn parse<'a>(&self, fragments: &'a [CommandFragment]) -> Result<O, CommandParseError<'a>> {
+        T::parse(fragments).map(self.map)
+    }
+}
+
+/// The commands registered by plugins at startup, assembled under the root command.
+///
+/// A command is selected by its name or aliases.
+/// Registering another command selected by any of the same names replaces the former one.
+pub struct PluginRegistry<O = Box<dyn Any + Send + Sync>> {
+    name: &'static str,
+    description: &'static str,
+    plugins: Vec<Box<dyn CommandPlugin<O>>>,
+}
+
+impl<O> PluginRegistry<O> {
+    /// Make a new [`PluginRegistry`] for the root command without any plugin.
+    pub fn new(name: &'static str, description: &'static str) -> Self {
+        PluginRegistry {
+            name,
+            description,
+            plugins: Vec::new(),
+        }
+    }
+
+    /// Register the plugin.
+    pub fn register(&mut self, plugin: impl CommandPlugin<O> + 'static) {
+        self.register_boxed(Box::new(plugin));
+    }
+
+    /// Register the plugin already boxed.
+    pub fn register_boxed(&mut self, plugin: Box<dyn CommandPlugin<O>>) {
+        self.plugins.retain(|registered| {
+            !names(registered.as_ref()).any(|name| names(plugin.as_ref()).any(|n| n == name))
+        });
+        self.plugins.push(plugin);
+    }
+
+    /// The plugins registered in registration order.
+    pub fn plugins(&self) -> impl Iterator<Item = &dyn CommandPlugin<O>> + '_ {
+        self.plugins.iter().map(AsRef::as_ref)
+    }
+
+    /// Assemble the spec of root command with the commands registered as its subcommands.
+    pub fn spec(&self) -> CommandSpec {
+        CommandSpec {
+            name: self.name,
+            description: self.description,
+            aliases: Vec::new(),
+            options: Vec::new(),
+            exclusive: Vec::new(),
+            permissions: CommandPermissions::default(),
+            cooldown: None,
+            confirm: None,
+            self_execution: Default::default(),
+            subcommands: self
+                .plugins
+                .iter()
+                .map(|plugin| plugin.spec().clone())
+                .collect(),
+        }
+    }
+
+    /// Parse [`CommandFragment`] sequence following the name of root command,
+    /// starting with [`CommandFragment::Select`] of the command registered.
+    pub fn parse<'a>(&self, fragments: &'a [CommandFragment]) -> Result<O, CommandParseError<'a>> {
+        match fragments {
+            [CommandFragment::Select(name), rest @ ..] => {
+                let plugin = self
+                    .plugins
+                    .iter()
+                    .find(|plugin| names(plugin.as_ref()).any(|n| n == name));
+                match plugin {
+                    Some(plugin) => plugin.parse(rest),
+                    None => Err(CommandParseError::UnknownCommand(name)),
+                }
+            }
+            [CommandFragment::Execute(_), ..] => Err(CommandParseError::ExecuteTooEarly),
+            [] => Err(CommandParseError::IncompleteCommand),
+        }
+    }
+}
+
+/// The name and aliases the command of plugin is selected by.
+fn names<O>(plugin: &dyn CommandPlugin<O>) -> impl Iterator<Item = &'static str> + '_ {
+    std::iter::once(plugin.name()).chain(plugin.spec().aliases.iter().copied())
+}
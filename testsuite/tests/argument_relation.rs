@@ -0,0 +1,77 @@
+use kal::{Command, CommandArgument, CommandArgumentValue, CommandFragment, CommandParseError};
+use pretty_assertions::assert_eq;
+
+#[derive(Debug, PartialEq, Command)]
+enum Mute {
+    #[command(self, exclusive(user, role))]
+    Mute {
+        user: Option<String>,
+        role: Option<String>,
+        temporary: Option<String>,
+        #[argument(requires = "temporary")]
+        duration: Option<i64>,
+    },
+    #[command(exclusive(all, user))]
+    Clear {
+        all: Option<i64>,
+        user: Option<String>,
+    },
+}
+
+fn named(name: &str, value: CommandArgumentValue) -> CommandArgument {
+    CommandArgument::Named(name.to_string(), value)
+}
+
+#[test]
+fn relation_spec() {
+    let spec = Mute::spec();
+    assert_eq!(spec.exclusive, vec![vec!["user", "role"]]);
+    assert_eq!(spec.options[3].requires, vec!["temporary"]);
+    assert!(spec.options[0].requires.is_empty());
+    assert_eq!(spec.subcommands[0].exclusive, vec![vec!["all", "user"]]);
+}
+
+#[test]
+fn relation_parse() {
+    use CommandArgumentValue::{String as S, I64};
+
+    assert_eq!(
+        Ok(Mute::Mute {
+            user: None,
+            role: Some("mods".to_string()),
+            temporary: Some("yes".to_string()),
+            duration: Some(10),
+        }),
+        Mute::parse(&[CommandFragment::Execute(vec![
+            named("role", S("mods".into())),
+            named("temporary", S("yes".into())),
+            named("duration", I64(10)),
+        ])])
+    );
+    assert_eq!(
+        Err(CommandParseError::ConflictingArguments(vec![
+            "user", "role"
+        ])),
+        Mute::parse(&[CommandFragment::Execute(vec![
+            CommandArgument::Positioned(0, S("alice".into())),
+            named("role", S("mods".into())),
+        ])])
+    );
+    assert_eq!(
+        Err(CommandParseError::MissingRequiredArgument {
+            argument: "duration",
+            requires: "temporary",
+        }),
+        Mute::parse(&[CommandFragment::Execute(vec![
+            named("user", S("alice".into())),
+            named("duration", I64(10)),
+        ])])
+    );
+    assert_eq!(
+        Err(CommandParseError::ConflictingArguments(vec!["all", "user"])),
+        Mute::parse(&[
+            CommandFragment::Select("clear".to_string()),
+            CommandFragment::Execute(vec![named("user", S("alice".into())), named("all", I64(1))]),
+        ])
+    );
+}
@@ -0,0 +1,169 @@
+use kal::{
+    complete, Command, CommandArgument, CommandArgumentValue, CommandFragment, CommandParseError,
+    Completion, CompletionKind,
+};
+use pretty_assertions::assert_eq;
+
+/// Music player
+#[derive(Debug, PartialEq, Command)]
+enum Music {
+    /// Play a song
+    Play {
+        /// The song to play
+        song: String,
+        /// The volume
+        #[argument(choice = 25, choice = 50, choice = 100)]
+        volume: Option<i64>,
+    },
+    /// Play the song again
+    Replay,
+    /// Set the loop mode
+    Loop {
+        /// How to loop
+        #[argument(choice = "off", choice = "one song", choice = "queue")]
+        mode: String,
+    },
+}
+
+fn values(completions: Vec<Completion>) -> Vec<(String, CompletionKind)> {
+    completions
+        .into_iter()
+        .map(|completion| (completion.value, completion.kind))
+        .collect()
+}
+
+#[test]
+fn complete_subcommand() {
+    let spec = Music::spec();
+    assert_eq!(
+        complete(&spec, "re", 2),
+        vec![Completion {
+            value: "replay".to_string(),
+            description: "Play the song again".to_string(),
+            kind: CompletionKind::Subcommand,
+            span: 0..2,
+        }]
+    );
+    assert_eq!(
+        values(complete(&spec, "", 0)),
+        vec![
+            ("play".to_string(), CompletionKind::Subcommand),
+            ("replay".to_string(), CompletionKind::Subcommand),
+            ("loop".to_string(), CompletionKind::Subcommand),
+        ]
+    );
+    assert_eq!(
+        values(complete(&spec, "pl and more", 2)),
+        vec![("play".to_string(), CompletionKind::Subcommand)]
+    );
+}
+
+#[test]
+fn complete_cursor_clamped() {
+    let spec = Music::spec();
+    assert_eq!(complete(&spec, "pl", 10), complete(&spec, "pl", 2));
+    // The cursor inside `é` is moved back to its start.
+    assert_eq!(complete(&spec, "play é", 6), complete(&spec, "play é", 5));
+    assert_eq!(
+        values(complete(&spec, "play é", 6)),
+        values(complete(&spec, "play ", 5))
+    );
+}
+
+#[test]
+fn complete_argument() {
+    let spec = Music::spec();
+    assert_eq!(
+        values(complete(&spec, "play ", 5)),
+        vec![
+            ("song=".to_string(), CompletionKind::ArgumentName),
+            ("volume=".to_string(), CompletionKind::ArgumentName),
+        ]
+    );
+    assert_eq!(
+        values(complete(&spec, "play song=abc v", 15)),
+        vec![("volume=".to_string(), CompletionKind::ArgumentName)]
+    );
+    assert_eq!(
+        values(complete(&spec, "play abc ", 9)),
+        vec![
+            ("volume=".to_string(), CompletionKind::ArgumentName),
+            ("25".to_string(), CompletionKind::Choice),
+            ("50".to_string(), CompletionKind::Choice),
+            ("100".to_string(), CompletionKind::Choice),
+        ]
+    );
+    assert_eq!(
+        complete(&spec, "play abc volume=1", 17),
+        vec![Completion {
+            value: "100".to_string(),
+            description: "The volume".to_string(),
+            kind: CompletionKind::Choice,
+            span: 16..17,
+        }]
+    );
+    assert_eq!(
+        values(complete(&spec, "play abc volume=", 16)),
+        vec![
+            ("25".to_string(), CompletionKind::Choice),
+            ("50".to_string(), CompletionKind::Choice),
+            ("100".to_string(), CompletionKind::Choice),
+        ]
+    );
+}
+
+#[test]
+fn complete_unclosed_quote() {
+    let spec = Music::spec();
+    assert_eq!(
+        complete(&spec, r#"loop "one"#, 9),
+        vec![Completion {
+            value: r#""one song""#.to_string(),
+            description: "How to loop".to_string(),
+            kind: CompletionKind::Choice,
+            span: 5..9,
+        }]
+    );
+    assert_eq!(
+        complete(&spec, r#"loop mode="o"#, 12),
+        vec![
+            Completion {
+                value: "off".to_string(),
+                description: "How to loop".to_string(),
+                kind: CompletionKind::Choice,
+                span: 10..12,
+            },
+            Completion {
+                value: r#""one song""#.to_string(),
+                description: "How to loop".to_string(),
+                kind: CompletionKind::Choice,
+                span: 10..12,
+            },
+        ]
+    );
+}
+
+#[test]
+fn choice_validation() {
+    assert_eq!(
+        Music::parse(&[
+            CommandFragment::Select("loop".to_string()),
+            CommandFragment::Execute(vec![CommandArgument::Positioned(
+                0,
+                CommandArgumentValue::String("all".to_string()),
+            )]),
+        ]),
+        Err(CommandParseError::ValidationFailed {
+            argument: "mode",
+            reason: "must be one of off, one song, queue".to_string(),
+        })
+    );
+    assert_eq!(
+        Music::spec().subcommands[0].options[1].validation.choices,
+        vec![
+            CommandArgumentValue::I64(25),
+            CommandArgumentValue::I64(50),
+            CommandArgumentValue::I64(100),
+        ]
+    );
+}
@@ -0,0 +1,183 @@
+use kal::{
+    complete, complete_async, dispatch::BoxFuture, Command, CommandArgument, CommandArgumentValue,
+    Completion, CompletionContext, CompletionKind, CompletionProvider,
+};
+use pretty_assertions::assert_eq;
+
+const PLAYLISTS: &[&str] = &["chill", "chiptune", "road trip", "rock"];
+
+const SONGS: &[(&str, &str)] = &[("chill", "lofi"), ("chill", "rain"), ("rock", "thunder")];
+
+struct Playlists;
+
+impl CompletionProvider for Playlists {
+    fn complete(&self, context: &CompletionContext<'_>) -> Vec<CommandArgumentValue> {
+        PLAYLISTS
+            .iter()
+            .filter(|playlist| playlist.starts_with(context.prefix))
+            .map(|playlist| CommandArgumentValue::String(playlist.to_string()))
+            .collect()
+    }
+}
+
+static PLAYLIST_PROVIDER: Playlists = Playlists;
+
+/// Look up the songs of the playlist given before.
+struct Songs;
+
+impl CompletionProvider for Songs {
+    fn complete_async<'a>(
+        &'a self,
+        context: &'a CompletionContext<'a>,
+    ) -> BoxFuture<'a, Vec<CommandArgumentValue>> {
+        Box::pin(async move {
+            assert_eq!(context.path.last().unwrap().name, "add");
+            let playlist = context
+                .arguments
+                .iter()
+                .find_map(|argument| match argument {
+                    CommandArgument::Named(name, CommandArgumentValue::String(playlist))
+                        if name == "playlist" =>
+                    {
+                        Some(playlist.as_str())
+                    }
+                    CommandArgument::Positioned(0, CommandArgumentValue::String(playlist)) => {
+                        Some(playlist.as_str())
+                    }
+                    _ => None,
+                });
+            SONGS
+                .iter()
+                .filter(|(of, song)| Some(*of) == playlist && song.starts_with(context.prefix))
+                .map(|(_, song)| CommandArgumentValue::String(song.to_string()))
+                .collect()
+        })
+    }
+}
+
+/// Manage playlists
+#[derive(Debug, PartialEq, Command)]
+enum Playlist {
+    /// Add a song to the playlist
+    Add {
+        /// The playlist to add to
+        #[argument(complete = PLAYLIST_PROVIDER)]
+        playlist: String,
+        /// The song to add
+        #[argument(complete = "Songs")]
+        song: String,
+    },
+}
+
+fn values(completions: Vec<Completion>) -> Vec<(String, CompletionKind)> {
+    completions
+        .into_iter()
+        .map(|completion| (completion.value, completion.kind))
+        .collect()
+}
+
+#[test]
+fn complete_with_provider() {
+    let spec = Playlist::spec();
+    assert_eq!(
+        complete(&spec, "add ch", 6),
+        vec![
+            Completion {
+                value: "chill".to_string(),
+                description: "The playlist to add to".to_string(),
+                kind: CompletionKind::Choice,
+                span: 4..6,
+            },
+            Completion {
+                value: "chiptune".to_string(),
+                description: "The playlist to add to".to_string(),
+                kind: CompletionKind::Choice,
+                span: 4..6,
+            },
+        ]
+    );
+    assert_eq!(
+        values(complete(&spec, "add playlist=ro", 15)),
+        vec![
+            ("\"road trip\"".to_string(), CompletionKind::Choice),
+            ("rock".to_string(), CompletionKind::Choice),
+        ]
+    );
+}
+
+#[test]
+fn complete_with_async_provider() {
+    let spec = Playlist::spec();
+    assert_eq!(
+        values(pollster::block_on(complete_async(&spec, "add chill ", 10))),
+        vec![
+            ("song=".to_string(), CompletionKind::ArgumentName),
+            ("lofi".to_string(), CompletionKind::Choice),
+            ("rain".to_string(), CompletionKind::Choice),
+        ]
+    );
+    assert_eq!(
+        values(pollster::block_on(complete_async(
+            &spec,
+            "add song= playlist=rock",
+            9
+        ))),
+        vec![]
+    );
+    assert_eq!(
+        values(pollster::block_on(complete_async(
+            &spec,
+            "add playlist=rock song=t",
+            24
+        ))),
+        vec![("thunder".to_string(), CompletionKind::Choice)]
+    );
+}
+
+#[test]
+fn complete_sync_without_async_values() {
+    let spec = Playlist::spec();
+    assert_eq!(
+        values(complete(&spec, "add chill ", 10)),
+        vec![("song=".to_string(), CompletionKind::ArgumentName)]
+    );
+    assert_eq!(
+        values(pollster::block_on(complete_async(&spec, "add ro", 6))),
+        vec![
+            ("\"road trip\"".to_string(), CompletionKind::Choice),
+            ("rock".to_string(), CompletionKind::Choice),
+        ]
+    );
+}
+
+#[test]
+fn provider_in_spec() {
+    let spec = Playlist::spec();
+    let options = &spec.subcommands[0].options;
+    assert_eq!(
+        options[0].completion_provider,
+        Some(&PLAYLIST_PROVIDER as &dyn CompletionProvider)
+    );
+    assert_eq!(
+        options[1].completion_provider,
+        Some(&Songs as &dyn CompletionProvider)
+    );
+    assert_ne!(
+        options[0].completion_provider,
+        options[1].completion_provider
+    );
+    assert_eq!(Playlist::spec(), spec);
+    assert_eq!(
+        Playlist::parse(&[
+            kal::CommandFragment::Select("add".to_string()),
+            kal::CommandFragment::Execute(vec![
+                CommandArgument::Positioned(0, CommandArgumentValue::String("rock".to_string())),
+                CommandArgument::Positioned(1, CommandArgumentValue::String("x".to_string())),
+            ]),
+        ]),
+        Ok(Playlist::Add {
+            playlist: "rock".to_string(),
+            song: "x".to_string(),
+        })
+    );
+}
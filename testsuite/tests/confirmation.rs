@@ -0,0 +1,220 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use kal::{
+    dispatch::{Dispatcher, Handler},
+    lex::TransformHintProvider,
+    Command, CommandArgument, CommandArgumentValue, CommandFragment, Confirmation,
+    ConfirmationCaller, ConfirmationTracker, PendingConfirmation, CONFIRM_ARGUMENT,
+};
+use pretty_assertions::assert_eq;
+
+#[derive(Debug, PartialEq, Command, TransformHintProvider)]
+#[command(to_fragments)]
+enum Purge {
+    #[command(confirm = "This will delete {count} messages")]
+    Messages {
+        count: i64,
+    },
+    Preview {
+        count: i64,
+    },
+}
+
+/// Executing `mod` itself resets the settings, which `mod list` beside doesn't confirm.
+#[derive(Debug, PartialEq, Command, TransformHintProvider)]
+#[command(to_fragments)]
+enum Mod {
+    #[command(self, confirm = "This will reset the settings")]
+    Reset {},
+    List,
+}
+
+fn purge(subcommand: &str, count: i64) -> Vec<CommandFragment> {
+    vec![
+        CommandFragment::Select(subcommand.to_string()),
+        CommandFragment::Execute(vec![CommandArgument::Positioned(
+            0,
+            CommandArgumentValue::I64(count),
+        )]),
+    ]
+}
+
+struct User(&'static str);
+
+impl ConfirmationCaller for User {
+    fn confirmer_id(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+const ALICE: User = User("alice");
+
+fn with_token(mut fragments: Vec<CommandFragment>, token: &str) -> Vec<CommandFragment> {
+    if let Some(CommandFragment::Execute(arguments)) = fragments.last_mut() {
+        arguments.push(CommandArgument::Named(
+            CONFIRM_ARGUMENT.to_string(),
+            CommandArgumentValue::String(token.to_string()),
+        ));
+    }
+    fragments
+}
+
+fn pending(confirmation: Confirmation<Purge>) -> PendingConfirmation {
+    match confirmation {
+        Confirmation::Pending(pending) => pending,
+        Confirmation::Confirmed(command) => panic!("unexpected {:?}", command),
+    }
+}
+
+#[test]
+fn confirmation_spec() {
+    let spec = Purge::spec();
+    assert_eq!(spec.confirm, None);
+    assert_eq!(
+        spec.subcommands[0].confirm,
+        Some("This will delete {count} messages")
+    );
+    assert_eq!(spec.subcommands[1].confirm, None);
+}
+
+#[test]
+fn confirmation_parse() {
+    let tracker = ConfirmationTracker::new(Duration::from_secs(60));
+    assert_eq!(
+        Confirmation::parse(&purge("preview", 10), &tracker, &ALICE),
+        Ok(Confirmation::Confirmed(Purge::Preview { count: 10 }))
+    );
+
+    let pending = pending(Confirmation::parse(&purge("messages", 10), &tracker, &ALICE).unwrap());
+    assert_eq!(pending.message, "This will delete 10 messages");
+    assert_eq!(
+        pending.confirmed,
+        with_token(purge("messages", 10), &pending.token)
+    );
+
+    // The token doesn't confirm the other arguments, nor the other caller.
+    let other = with_token(purge("messages", 20), &pending.token);
+    assert_eq!(
+        self::pending(Confirmation::parse(&other, &tracker, &ALICE).unwrap()).message,
+        "This will delete 20 messages"
+    );
+    assert!(matches!(
+        Confirmation::<Purge>::parse(&pending.confirmed, &tracker, &User("bob")),
+        Ok(Confirmation::Pending(_))
+    ));
+
+    assert_eq!(
+        Confirmation::parse(&pending.confirmed, &tracker, &ALICE),
+        Ok(Confirmation::Confirmed(Purge::Messages { count: 10 }))
+    );
+    // The token is used up.
+    assert!(matches!(
+        Confirmation::<Purge>::parse(&pending.confirmed, &tracker, &ALICE),
+        Ok(Confirmation::Pending(_))
+    ));
+}
+
+#[test]
+fn confirmation_tracker() {
+    let now = Arc::new(Mutex::new(Instant::now()));
+    let tracker = ConfirmationTracker::with_clock(Duration::from_secs(60), {
+        let now = now.clone();
+        move || *now.lock().unwrap()
+    });
+
+    let first = pending(Confirmation::parse(&purge("messages", 1), &tracker, &ALICE).unwrap());
+    let second = pending(Confirmation::parse(&purge("messages", 1), &tracker, &ALICE).unwrap());
+    assert_ne!(first.token, second.token);
+
+    *now.lock().unwrap() += Duration::from_secs(60);
+    assert!(matches!(
+        Confirmation::<Purge>::parse(&first.confirmed, &tracker, &ALICE),
+        Ok(Confirmation::Pending(_))
+    ));
+
+    // The tokens are not shared with another tracker, like the one after a restart.
+    let third = pending(Confirmation::parse(&purge("messages", 1), &tracker, &ALICE).unwrap());
+    let restarted = ConfirmationTracker::new(Duration::from_secs(60));
+    assert!(matches!(
+        Confirmation::<Purge>::parse(&third.confirmed, &restarted, &ALICE),
+        Ok(Confirmation::Pending(_))
+    ));
+    assert_eq!(
+        Confirmation::parse(&third.confirmed, &tracker, &ALICE),
+        Ok(Confirmation::Confirmed(Purge::Messages { count: 1 }))
+    );
+}
+
+impl Handler<User> for Purge {
+    type Output = String;
+
+    async fn handle(self, _ctx: &User) -> String {
+        format!("{:?}", self)
+    }
+}
+
+#[test]
+fn confirmation_dispatch() {
+    let dispatcher = Dispatcher::new().route::<Purge>().confirmation(
+        ConfirmationTracker::new(Duration::from_secs(60)),
+        |pending: PendingConfirmation, _: &User| pending.message,
+    );
+    let mut fragments = purge("messages", 5);
+    fragments.insert(0, CommandFragment::Select("purge".to_string()));
+
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &ALICE)),
+        Ok("This will delete 5 messages".to_string())
+    );
+
+    let dispatcher = Dispatcher::new().route::<Purge>().confirmation(
+        ConfirmationTracker::new(Duration::from_secs(60)),
+        |pending: PendingConfirmation, _: &User| pending.token,
+    );
+    let token = pollster::block_on(dispatcher.dispatch(&fragments, &ALICE)).unwrap();
+    let message = format!("purge messages 5 {}={}", CONFIRM_ARGUMENT, token);
+    let fragments = dispatcher.transform(&message).unwrap();
+    let asked_bob = pollster::block_on(dispatcher.dispatch(&fragments, &User("bob"))).unwrap();
+    assert_ne!(asked_bob, token);
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &ALICE)),
+        Ok("Messages { count: 5 }".to_string())
+    );
+    assert_ne!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &ALICE)),
+        Ok("Messages { count: 5 }".to_string())
+    );
+}
+
+#[test]
+fn confirmation_self_variant() {
+    assert_eq!(Mod::spec().confirm, None);
+    assert_eq!(
+        Mod::spec().self_execution.confirm,
+        Some("This will reset the settings")
+    );
+
+    let tracker = ConfirmationTracker::new(Duration::from_secs(60));
+    let list = vec![
+        CommandFragment::Select("list".to_string()),
+        CommandFragment::Execute(vec![]),
+    ];
+    assert_eq!(
+        Confirmation::<Mod>::parse(&list, &tracker, &ALICE),
+        Ok(Confirmation::Confirmed(Mod::List))
+    );
+
+    let reset = vec![CommandFragment::Execute(vec![])];
+    let Ok(Confirmation::<Mod>::Pending(pending)) = Confirmation::parse(&reset, &tracker, &ALICE)
+    else {
+        panic!("reset should wait for confirmation");
+    };
+    assert_eq!(pending.message, "This will reset the settings");
+    assert_eq!(
+        Confirmation::parse(&pending.confirmed, &tracker, &ALICE),
+        Ok(Confirmation::Confirmed(Mod::Reset {}))
+    );
+}
@@ -0,0 +1,107 @@
+use kal::{
+    Command, CommandArgument, CommandArgumentValue, CommandFragment, CommandParseError,
+    Conversation, ConversationStep,
+};
+use pretty_assertions::assert_eq;
+
+#[derive(Debug, PartialEq, Command)]
+enum Remind {
+    At {
+        /// The hour to remind at
+        #[argument(min = 0, max = 23)]
+        hour: i64,
+        /// What to remind
+        message: String,
+        repeat: Option<i64>,
+    },
+}
+
+fn prompted(step: ConversationStep<Remind>) -> String {
+    match step {
+        ConversationStep::Ask(prompt) => prompt.to_string(),
+        ConversationStep::Done(command) => panic!("unexpected {:?}", command),
+    }
+}
+
+#[test]
+fn conversation_fill() {
+    let mut conversation = Conversation::new(vec![CommandFragment::Select("at".to_string())]);
+
+    assert_eq!(
+        prompted(conversation.poll().unwrap()),
+        "Enter hour (integer): The hour to remind at"
+    );
+    assert_eq!(conversation.asking(), Some("hour"));
+    assert_eq!(
+        prompted(conversation.reply(" 9 ").unwrap()),
+        "Enter message (text): What to remind"
+    );
+    assert_eq!(
+        conversation.reply::<Remind>("stretch").unwrap(),
+        ConversationStep::Done(Remind::At {
+            hour: 9,
+            message: "stretch".to_string(),
+            repeat: None,
+        })
+    );
+    assert_eq!(conversation.asking(), None);
+}
+
+#[test]
+fn conversation_revalidate() {
+    let mut conversation = Conversation::new(vec![
+        CommandFragment::Select("at".to_string()),
+        CommandFragment::Execute(vec![CommandArgument::Named(
+            "message".to_string(),
+            CommandArgumentValue::String("stretch".to_string()),
+        )]),
+    ]);
+
+    assert_eq!(
+        prompted(conversation.poll().unwrap()),
+        "Enter hour (integer): The hour to remind at"
+    );
+    assert_eq!(
+        conversation.reply::<Remind>("nine"),
+        Err(CommandParseError::ValidationFailed {
+            argument: "hour",
+            reason: "must be an integer".to_string(),
+        })
+    );
+    assert_eq!(
+        conversation.reply::<Remind>("24"),
+        Err(CommandParseError::ValidationFailed {
+            argument: "hour",
+            reason: "must be at most 23".to_string(),
+        })
+    );
+    assert_eq!(conversation.asking(), Some("hour"));
+    assert_eq!(
+        conversation.reply::<Remind>("23").unwrap(),
+        ConversationStep::Done(Remind::At {
+            hour: 23,
+            message: "stretch".to_string(),
+            repeat: None,
+        })
+    );
+}
+
+#[test]
+fn conversation_serialize() {
+    let mut conversation = Conversation::new(vec![CommandFragment::Select("at".to_string())]);
+    conversation.poll::<Remind>().unwrap();
+    conversation.reply::<Remind>("7").unwrap();
+
+    let saved = serde_json::to_string(&conversation).unwrap();
+    let mut restored: Conversation = serde_json::from_str(&saved).unwrap();
+    assert_eq!(restored, conversation);
+    assert_eq!(restored.asking(), Some("message"));
+    assert_eq!(
+        restored.reply::<Remind>("drink water").unwrap(),
+        ConversationStep::Done(Remind::At {
+            hour: 7,
+            message: "drink water".to_string(),
+            repeat: None,
+        })
+    );
+}
@@ -0,0 +1,292 @@
+use std::{
+    any::Any,
+    ops::ControlFlow,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use kal::{
+    dispatch::{BoxFuture, Cooldowns, Dispatcher, Handler, Middleware},
+    lex::TransformHintProvider,
+    Command, CommandCooldown, CommandFragment, CommandGroup, CommandSpec, CooldownCaller,
+    CooldownTracker, RetryAfter,
+};
+use pretty_assertions::assert_eq;
+
+#[derive(Debug, Command, TransformHintProvider)]
+#[command(cooldown = "5s", per = "user")]
+#[command(to_fragments)]
+struct Search {
+    query: String,
+}
+
+#[derive(Debug, Command, TransformHintProvider)]
+#[command(cooldown = "1m", per = "channel")]
+enum Render {
+    #[command(cooldown = "500ms")]
+    Image,
+    Text,
+}
+
+/// Executing `quote` itself picks a random quote, throttled apart from `quote add`.
+#[derive(Debug, Command, TransformHintProvider)]
+#[allow(dead_code)]
+enum Quote {
+    #[command(self, cooldown = "10s", per = "user")]
+    Random {},
+    Add {
+        text: String,
+    },
+}
+
+#[derive(Debug, Command, TransformHintProvider)]
+struct Ping;
+
+#[derive(CommandGroup)]
+#[allow(dead_code)]
+enum Root {
+    Search(Search),
+    #[command(cooldown = "1h", per = "global")]
+    Ping(Ping),
+}
+
+struct Caller {
+    user: &'static str,
+    channel: &'static str,
+}
+
+impl CooldownCaller for Caller {
+    fn caller_id(&self, per: &str) -> String {
+        match per {
+            "user" => self.user.to_string(),
+            "channel" => self.channel.to_string(),
+            _ => String::new(),
+        }
+    }
+}
+
+fn manual_clock() -> (Arc<Mutex<Instant>>, impl Fn() -> Instant) {
+    let now = Arc::new(Mutex::new(Instant::now()));
+    let clock = {
+        let now = now.clone();
+        move || *now.lock().unwrap()
+    };
+    (now, clock)
+}
+
+fn advance(now: &Mutex<Instant>, duration: Duration) {
+    *now.lock().unwrap() += duration;
+}
+
+#[test]
+fn cooldown_spec() {
+    assert_eq!(
+        Search::spec().cooldown,
+        Some(CommandCooldown {
+            duration: Duration::from_secs(5),
+            per: "user",
+        })
+    );
+    assert_eq!(
+        Render::spec().cooldown,
+        Some(CommandCooldown {
+            duration: Duration::from_secs(60),
+            per: "channel",
+        })
+    );
+    assert_eq!(
+        Render::spec().subcommands[0].cooldown,
+        Some(CommandCooldown {
+            duration: Duration::from_millis(500),
+            per: "user",
+        })
+    );
+    assert_eq!(Render::spec().subcommands[1].cooldown, None);
+    assert_eq!(
+        Root::spec().subcommands[1].cooldown,
+        Some(CommandCooldown {
+            duration: Duration::from_secs(60 * 60),
+            per: "global",
+        })
+    );
+}
+
+#[test]
+fn cooldown_tracker() {
+    let (now, clock) = manual_clock();
+    let tracker = CooldownTracker::with_clock(clock);
+    let alice = Caller {
+        user: "alice",
+        channel: "general",
+    };
+    let bob = Caller {
+        user: "bob",
+        channel: "general",
+    };
+
+    let search = Search::spec();
+    // Checking doesn't start the cooldown.
+    assert_eq!(tracker.check(&[&search], &alice), Ok(()));
+    assert_eq!(tracker.check(&[&search], &alice), Ok(()));
+    assert_eq!(tracker.start(&[&search], &alice), Ok(()));
+    assert_eq!(tracker.start(&[&search], &bob), Ok(()));
+    advance(&now, Duration::from_secs(2));
+    assert_eq!(
+        tracker.check(&[&search], &alice),
+        Err(RetryAfter {
+            command: vec!["search"],
+            remaining: Duration::from_secs(3),
+        })
+    );
+    assert_eq!(
+        tracker.start(&[&search], &alice),
+        Err(RetryAfter {
+            command: vec!["search"],
+            remaining: Duration::from_secs(3),
+        })
+    );
+    advance(&now, Duration::from_secs(3));
+    assert_eq!(tracker.start(&[&search], &alice), Ok(()));
+
+    let render = Render::spec();
+    let image = &render.subcommands[0];
+    let text = &render.subcommands[1];
+    assert_eq!(tracker.start(&[&render, image], &alice), Ok(()));
+    assert_eq!(
+        tracker.start(&[&render, text], &bob),
+        Err(RetryAfter {
+            command: vec!["render"],
+            remaining: Duration::from_secs(60),
+        })
+    );
+    tracker.clear_expired();
+    advance(&now, Duration::from_secs(60));
+    assert_eq!(tracker.start(&[&render, text], &bob), Ok(()));
+}
+
+#[test]
+fn cooldown_self_variant() {
+    assert_eq!(Quote::spec().cooldown, None);
+    assert_eq!(
+        Quote::spec().self_execution.cooldown,
+        Some(CommandCooldown {
+            duration: Duration::from_secs(10),
+            per: "user",
+        })
+    );
+
+    let (now, clock) = manual_clock();
+    let tracker = CooldownTracker::with_clock(clock);
+    let alice = Caller {
+        user: "alice",
+        channel: "general",
+    };
+
+    let quote = Quote::spec();
+    let add = &quote.subcommands[0];
+    assert_eq!(tracker.start(&[&quote], &alice), Ok(()));
+    advance(&now, Duration::from_secs(1));
+    assert_eq!(
+        tracker.start(&[&quote], &alice),
+        Err(RetryAfter {
+            command: vec!["quote"],
+            remaining: Duration::from_secs(9),
+        })
+    );
+    assert_eq!(tracker.start(&[&quote, add], &alice), Ok(()));
+    assert_eq!(tracker.start(&[&quote, add], &alice), Ok(()));
+}
+
+impl Handler<Caller> for Search {
+    type Output = String;
+
+    async fn handle(self, _ctx: &Caller) -> String {
+        format!("results for {}", self.query)
+    }
+}
+
+#[test]
+fn cooldown_dispatch() {
+    let (now, clock) = manual_clock();
+    let dispatcher = Dispatcher::new().route::<Search>().layer(Cooldowns::new(
+        CooldownTracker::with_clock(clock),
+        |retry_after: RetryAfter, ctx: &Caller| format!("{}: {}", ctx.user, retry_after),
+    ));
+    let alice = Caller {
+        user: "alice",
+        channel: "general",
+    };
+    let fragments = vec![
+        CommandFragment::Select("search".to_string()),
+        CommandFragment::Execute(vec![kal::CommandArgument::Positioned(
+            0,
+            kal::CommandArgumentValue::String("kal".to_string()),
+        )]),
+    ];
+
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &alice)),
+        Ok("results for kal".to_string())
+    );
+    advance(&now, Duration::from_millis(1500));
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &alice)),
+        Ok("alice: Retry search after 3.5s".to_string())
+    );
+}
+
+/// Stop the searches for nothing after the cooldowns are checked.
+struct RejectEmpty;
+
+impl Middleware<Caller, String> for RejectEmpty {
+    fn after_parse<'a>(
+        &'a self,
+        _path: &'a [&'static CommandSpec],
+        command: &'a (dyn Any + Send + Sync),
+        _ctx: &'a Caller,
+    ) -> BoxFuture<'a, ControlFlow<String>> {
+        let flow = match command.downcast_ref::<Search>() {
+            Some(search) if search.query.is_empty() => ControlFlow::Break("empty".to_string()),
+            _ => ControlFlow::Continue(()),
+        };
+        Box::pin(async { flow })
+    }
+}
+
+#[test]
+fn cooldown_starts_on_handle() {
+    let (_, clock) = manual_clock();
+    let dispatcher = Dispatcher::new()
+        .route::<Search>()
+        .layer(Cooldowns::new(
+            CooldownTracker::with_clock(clock),
+            |retry_after: RetryAfter, _: &Caller| retry_after.to_string(),
+        ))
+        .layer(RejectEmpty);
+    let alice = Caller {
+        user: "alice",
+        channel: "general",
+    };
+    let search = |query: &str| {
+        vec![
+            CommandFragment::Select("search".to_string()),
+            CommandFragment::Execute(vec![kal::CommandArgument::Positioned(
+                0,
+                kal::CommandArgumentValue::String(query.to_string()),
+            )]),
+        ]
+    };
+
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&search(""), &alice)),
+        Ok("empty".to_string())
+    );
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&search("kal"), &alice)),
+        Ok("results for kal".to_string())
+    );
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&search("kal"), &alice)),
+        Ok("Retry search after 5.0s".to_string())
+    );
+}
@@ -0,0 +1,94 @@
+use kal::{
+    Command, CommandArgument, CommandArgumentValue, CommandArgumentValueType, CommandFragment,
+    CommandOptionValueKind, CommandOptionValueTy, ToArgumentValue, ToFragments,
+    TryFromArgumentValue, TryFromArgumentValueError,
+};
+use pretty_assertions::assert_eq;
+
+/// The id of user, which can be parsed but not rendered back.
+#[derive(Debug, PartialEq)]
+struct UserId(u64);
+
+impl TryFromArgumentValue for UserId {
+    fn try_from_argument_value(
+        value: CommandArgumentValue,
+        _strict: bool,
+    ) -> Result<Self, TryFromArgumentValueError> {
+        match value {
+            CommandArgumentValue::I64(i) if i >= 0 => Ok(UserId(i as u64)),
+            value => Err(TryFromArgumentValueError {
+                expected_type: CommandArgumentValueType::I64,
+                actual_value: value,
+            }),
+        }
+    }
+}
+
+impl CommandOptionValueTy for UserId {
+    fn spec_kind() -> CommandOptionValueKind {
+        CommandOptionValueKind::Integer
+    }
+}
+
+/// The name of role, which can be rendered back as well.
+#[derive(Debug, PartialEq)]
+struct RoleName(String);
+
+impl TryFromArgumentValue for RoleName {
+    fn try_from_argument_value(
+        value: CommandArgumentValue,
+        strict: bool,
+    ) -> Result<Self, TryFromArgumentValueError> {
+        String::try_from_argument_value(value, strict).map(RoleName)
+    }
+}
+
+impl CommandOptionValueTy for RoleName {
+    fn spec_kind() -> CommandOptionValueKind {
+        CommandOptionValueKind::String
+    }
+}
+
+impl ToArgumentValue for RoleName {
+    fn to_argument_value(&self) -> Option<CommandArgumentValue> {
+        Some(CommandArgumentValue::String(self.0.clone()))
+    }
+}
+
+#[derive(Debug, PartialEq, Command)]
+struct Kick {
+    user: UserId,
+}
+
+#[derive(Debug, PartialEq, Command)]
+#[command(to_fragments)]
+struct Grant {
+    role: RoleName,
+}
+
+#[test]
+fn custom_type_parse() {
+    assert_eq!(
+        Kick::parse(&[CommandFragment::Execute(vec![CommandArgument::Positioned(
+            0,
+            CommandArgumentValue::I64(42),
+        )])]),
+        Ok(Kick { user: UserId(42) })
+    );
+}
+
+#[test]
+fn custom_type_to_fragments() {
+    let grant = Grant {
+        role: RoleName("mod".to_string()),
+    };
+    let fragments = grant.to_fragments();
+    assert_eq!(
+        fragments,
+        vec![CommandFragment::Execute(vec![CommandArgument::Positioned(
+            0,
+            CommandArgumentValue::String("mod".to_string()),
+        )])]
+    );
+    assert_eq!(Grant::parse(&fragments), Ok(grant));
+}
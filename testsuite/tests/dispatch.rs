@@ -0,0 +1,216 @@
+use std::sync::Mutex;
+
+use kal::{
+    dispatch::{Dispatcher, Handler},
+    lex::{remove_leading, TransformHintProvider},
+    Command, CommandArgument, CommandArgumentValue, CommandFragment, CommandParseError,
+    CommandSpec, ToFragments,
+};
+use pretty_assertions::assert_eq;
+
+#[derive(Command, TransformHintProvider)]
+#[command(to_fragments)]
+struct Ping;
+
+#[derive(Command, TransformHintProvider)]
+#[command(to_fragments)]
+enum Note {
+    Add {
+        #[argument(take_rest)]
+        text: String,
+    },
+    Clear,
+}
+
+#[derive(Default)]
+struct Context {
+    notes: Mutex<Vec<String>>,
+}
+
+impl Handler<Context> for Ping {
+    type Output = String;
+
+    async fn handle(self, _ctx: &Context) -> String {
+        "pong".to_string()
+    }
+}
+
+impl Handler<Context> for Note {
+    type Output = String;
+
+    async fn handle(self, ctx: &Context) -> String {
+        let mut notes = ctx.notes.lock().unwrap();
+        match self {
+            Note::Add { text } => notes.push(text),
+            Note::Clear => notes.clear(),
+        }
+        format!("{} notes", notes.len())
+    }
+}
+
+fn dispatcher() -> Dispatcher<Context, String> {
+    Dispatcher::new().route::<Ping>().route::<Note>()
+}
+
+#[test]
+fn dispatch_fragments() {
+    let dispatcher = dispatcher();
+    let ctx = Context::default();
+
+    let fragments = vec![
+        CommandFragment::Select("ping".to_string()),
+        CommandFragment::Execute(vec![]),
+    ];
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &ctx)),
+        Ok("pong".to_string())
+    );
+
+    let fragments = vec![
+        CommandFragment::Select("note".to_string()),
+        CommandFragment::Select("add".to_string()),
+        CommandFragment::Execute(vec![CommandArgument::Positioned(
+            0,
+            CommandArgumentValue::String("buy milk".to_string()),
+        )]),
+    ];
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &ctx)),
+        Ok("1 notes".to_string())
+    );
+    assert_eq!(*ctx.notes.lock().unwrap(), vec!["buy milk".to_string()]);
+
+    let fragments = vec![
+        CommandFragment::Select("note".to_string()),
+        CommandFragment::Select("add".to_string()),
+        CommandFragment::Execute(vec![]),
+    ];
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &ctx)),
+        Err(CommandParseError::MissingArguments(vec!["text"]))
+    );
+
+    let fragments = vec![CommandFragment::Execute(vec![])];
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &ctx)),
+        Err(CommandParseError::ExecuteTooEarly)
+    );
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&[], &ctx)),
+        Err(CommandParseError::IncompleteCommand)
+    );
+}
+
+#[test]
+fn dispatch_unknown() {
+    let ctx = Context::default();
+    let fragments = vec![
+        CommandFragment::Select("pong".to_string()),
+        CommandFragment::Execute(vec![]),
+    ];
+
+    let dispatcher = dispatcher();
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &ctx)),
+        Err(CommandParseError::UnknownCommand(&"pong".to_string()))
+    );
+
+    let dispatcher =
+        dispatcher.fallback(|name, _| Box::pin(async move { format!("no {} here", name) }));
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &ctx)),
+        Ok("no pong here".to_string())
+    );
+}
+
+#[test]
+fn dispatch_message() {
+    let dispatcher = dispatcher().label_stripper(|s| remove_leading("/", s));
+    let ctx = Context::default();
+
+    let fragments = dispatcher.transform("/note add hello world").unwrap();
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &ctx)),
+        Ok("1 notes".to_string())
+    );
+    assert_eq!(*ctx.notes.lock().unwrap(), vec!["hello world".to_string()]);
+
+    let fragments = dispatcher.transform("/note clear").unwrap();
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &ctx)),
+        Ok("0 notes".to_string())
+    );
+
+    assert!(dispatcher.transform("note clear").is_err());
+    assert_eq!(
+        dispatcher.specs().map(|spec| spec.name).collect::<Vec<_>>(),
+        vec!["ping", "note"]
+    );
+}
+
+#[test]
+fn dispatch_is_send() {
+    fn assert_send<T: Send>(_: T) {}
+
+    let dispatcher = dispatcher();
+    let ctx = Context::default();
+    let fragments = vec![CommandFragment::Select("ping".to_string())];
+    assert_send(dispatcher.dispatch(&fragments, &ctx));
+}
+
+#[test]
+fn dispatch_displaced() {
+    /// The ping before the rewrite, answering to `p` as well.
+    struct OldPing;
+
+    impl Command for OldPing {
+        const NAME: &'static str = "ping";
+
+        fn spec() -> CommandSpec {
+            CommandSpec {
+                aliases: vec!["p"],
+                ..Ping::spec()
+            }
+        }
+
+        fn parse(fragments: &[CommandFragment]) -> Result<Self, CommandParseError<'_>> {
+            Ping::parse(fragments).map(|Ping| OldPing)
+        }
+    }
+
+    impl ToFragments for OldPing {
+        fn to_fragments(&self) -> Vec<CommandFragment> {
+            Ping.to_fragments()
+        }
+    }
+
+    impl Handler<Context> for OldPing {
+        type Output = String;
+
+        async fn handle(self, _ctx: &Context) -> String {
+            "old pong".to_string()
+        }
+    }
+
+    let dispatcher = Dispatcher::new()
+        .route::<OldPing>()
+        .route::<Note>()
+        .route::<Ping>()
+        .label_stripper(|s| remove_leading("/", s));
+    let ctx = Context::default();
+
+    assert_eq!(
+        dispatcher.specs().map(|spec| spec.name).collect::<Vec<_>>(),
+        vec!["note", "ping"]
+    );
+    let fragments = dispatcher.transform("/ping").unwrap();
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &ctx)),
+        Ok("pong".to_string())
+    );
+    let fragments = dispatcher.transform("/p").unwrap();
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &ctx)),
+        Err(CommandParseError::UnknownCommand(&"p".to_string()))
+    );
+}
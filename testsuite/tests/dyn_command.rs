@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+
+use kal::{
+    dyn_command::{
+        DynCommandOption, DynCommandRegistry, DynCommandSpec, DynCommandSpecError, ParsedInvocation,
+    },
+    Command, CommandArgument, CommandArgumentValue, CommandFragment, CommandOptionPattern,
+    CommandOptionValidation, CommandOptionValueKind, CommandParseError,
+};
+use pretty_assertions::assert_eq;
+
+/// Music player
+#[derive(Debug, PartialEq, Command)]
+enum Music {
+    /// Play a song
+    Play {
+        /// The song to play
+        song: String,
+        /// The volume
+        #[argument(max = 100)]
+        volume: Option<i64>,
+    },
+}
+
+#[derive(Debug, Command)]
+#[allow(dead_code)]
+enum Admin {
+    #[command(cooldown = "1m")]
+    Restart,
+}
+
+fn greet() -> DynCommandSpec {
+    DynCommandSpec::builder("greet")
+        .description("Greet someone")
+        .alias("hi")
+        .option(
+            DynCommandOption::new("name", CommandOptionValueKind::String)
+                .description("The name to greet")
+                .validation(CommandOptionValidation {
+                    pattern: Some(CommandOptionPattern::compile("^[a-z]+$").unwrap()),
+                    ..Default::default()
+                }),
+        )
+        .option(DynCommandOption::new("times", CommandOptionValueKind::Integer).optional())
+        .build()
+        .unwrap()
+}
+
+fn execute(arguments: Vec<CommandArgument>) -> CommandFragment {
+    CommandFragment::Execute(arguments)
+}
+
+fn string(s: &str) -> CommandArgumentValue {
+    CommandArgumentValue::String(s.to_string())
+}
+
+#[test]
+fn parse_dyn_command() {
+    let mut registry = DynCommandRegistry::new();
+    registry.register(greet());
+
+    let fragments = [
+        CommandFragment::Select("hi".to_string()),
+        execute(vec![
+            CommandArgument::Positioned(0, string("kal")),
+            CommandArgument::Named("times".to_string(), CommandArgumentValue::I64(2)),
+        ]),
+    ];
+    assert_eq!(
+        registry.parse(&fragments),
+        Ok(ParsedInvocation {
+            path: vec!["greet".to_string()],
+            args: HashMap::from([
+                ("name".to_string(), string("kal")),
+                ("times".to_string(), CommandArgumentValue::I64(2)),
+            ]),
+        })
+    );
+
+    let fragments = [
+        CommandFragment::Select("greet".to_string()),
+        execute(vec![CommandArgument::Named(
+            "name".to_string(),
+            string("kal"),
+        )]),
+    ];
+    assert_eq!(
+        registry.parse(&fragments),
+        Ok(ParsedInvocation {
+            path: vec!["greet".to_string()],
+            args: HashMap::from([("name".to_string(), string("kal"))]),
+        })
+    );
+
+    let fragments = [CommandFragment::Select("bye".to_string())];
+    assert_eq!(
+        registry.parse(&fragments),
+        Err(CommandParseError::UnknownCommand(&"bye".to_string()))
+    );
+}
+
+#[test]
+fn parse_dyn_command_rejected() {
+    let spec = greet();
+    assert_eq!(
+        spec.parse(&[execute(vec![CommandArgument::Positioned(0, string("KAL"))])]),
+        Err(CommandParseError::ValidationFailed {
+            argument: "name",
+            reason: "must match pattern `^[a-z]+$`".to_string(),
+        })
+    );
+    assert_eq!(
+        spec.parse(&[execute(vec![CommandArgument::Positioned(
+            1,
+            CommandArgumentValue::I64(2)
+        )])]),
+        Err(CommandParseError::MissingArguments(vec!["name"]))
+    );
+    assert_eq!(
+        spec.parse(&[execute(vec![
+            CommandArgument::Positioned(0, string("kal")),
+            CommandArgument::Positioned(1, string("twice")),
+        ])]),
+        Err(CommandParseError::MissingArguments(vec!["times"]))
+    );
+    assert_eq!(spec.parse(&[]), Err(CommandParseError::IncompleteCommand));
+}
+
+#[test]
+fn parse_dyn_command_relations() {
+    let spec = DynCommandSpec::builder("ban")
+        .option(DynCommandOption::new("user", CommandOptionValueKind::String).optional())
+        .option(DynCommandOption::new("role", CommandOptionValueKind::String).optional())
+        .option(
+            DynCommandOption::new("days", CommandOptionValueKind::Integer)
+                .optional()
+                .requires("reason"),
+        )
+        .option(DynCommandOption::new("reason", CommandOptionValueKind::String).optional())
+        .exclusive(["user", "role"])
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        spec.parse(&[execute(vec![
+            CommandArgument::Named("user".to_string(), string("a")),
+            CommandArgument::Named("role".to_string(), string("b")),
+        ])]),
+        Err(CommandParseError::ConflictingArguments(vec![
+            "user", "role"
+        ]))
+    );
+    assert_eq!(
+        spec.parse(&[execute(vec![CommandArgument::Named(
+            "days".to_string(),
+            CommandArgumentValue::I64(3)
+        )])]),
+        Err(CommandParseError::MissingRequiredArgument {
+            argument: "days",
+            requires: "reason",
+        })
+    );
+}
+
+#[test]
+fn build_dyn_command_rejected() {
+    let option = |name: &str| DynCommandOption::new(name, CommandOptionValueKind::String);
+    assert_eq!(
+        DynCommandSpec::builder("a")
+            .option(option("x"))
+            .option(option("x"))
+            .build(),
+        Err(DynCommandSpecError::DuplicateOption("x".to_string()))
+    );
+    assert_eq!(
+        DynCommandSpec::builder("a")
+            .option(option("x").requires("y"))
+            .build(),
+        Err(DynCommandSpecError::UnknownOption("y".to_string()))
+    );
+    assert_eq!(
+        DynCommandSpec::builder("a")
+            .option(DynCommandOption::new(
+                "x",
+                CommandOptionValueKind::Multiple(Box::new(CommandOptionValueKind::String))
+            ))
+            .build(),
+        Err(DynCommandSpecError::MultipleValues("x".to_string()))
+    );
+    assert_eq!(
+        DynCommandSpec::builder("a")
+            .subcommand(greet())
+            .subcommand(DynCommandSpec::builder("hi").build().unwrap())
+            .build(),
+        Err(DynCommandSpecError::DuplicateSubcommand("hi".to_string()))
+    );
+
+    let error = DynCommandSpec::builder("a")
+        .option(option("x").validation(CommandOptionValidation {
+            pattern: Some(CommandOptionPattern::new("(")),
+            ..Default::default()
+        }))
+        .build()
+        .unwrap_err();
+    assert!(
+        matches!(&error, DynCommandSpecError::InvalidPattern { option, .. } if option == "x"),
+        "{}",
+        error
+    );
+    let spec = DynCommandSpec::builder("a")
+        .option(option("x").validation(CommandOptionValidation {
+            pattern: Some(CommandOptionPattern::new("^x$")),
+            ..Default::default()
+        }))
+        .build()
+        .unwrap();
+    assert!(spec.options[0]
+        .validation
+        .pattern
+        .as_ref()
+        .unwrap()
+        .regex()
+        .is_ok());
+}
+
+#[test]
+fn dyn_command_refuses_requirements() {
+    assert_eq!(
+        DynCommandSpec::try_from(Admin::spec_ref()),
+        Err(DynCommandSpecError::UnsupportedRequirement {
+            command: "restart".to_string(),
+            requirement: "cooldown",
+        })
+    );
+    assert!(DynCommandRegistry::new()
+        .register_command::<Admin>()
+        .is_err());
+}
+
+#[test]
+fn dyn_command_with_derived() {
+    let mut registry = DynCommandRegistry::new();
+    registry.register_command::<Music>().unwrap();
+    registry.register(
+        DynCommandSpec::builder("fun")
+            .subcommand(greet())
+            .subcommand(DynCommandSpec::try_from(Music::spec_ref()).unwrap())
+            .build()
+            .unwrap(),
+    );
+
+    let fragments = [
+        CommandFragment::Select("music".to_string()),
+        CommandFragment::Select("play".to_string()),
+        execute(vec![CommandArgument::Positioned(0, string("song"))]),
+    ];
+    let invocation = registry.parse(&fragments).unwrap();
+    assert_eq!(
+        invocation,
+        ParsedInvocation {
+            path: vec!["music".to_string(), "play".to_string()],
+            args: HashMap::from([("song".to_string(), string("song"))]),
+        }
+    );
+    assert_eq!(
+        Music::parse(&invocation.to_fragments()[1..]),
+        Ok(Music::Play {
+            song: "song".to_string(),
+            volume: None,
+        })
+    );
+
+    let fragments = [
+        CommandFragment::Select("fun".to_string()),
+        CommandFragment::Select("music".to_string()),
+        CommandFragment::Select("play".to_string()),
+        execute(vec![
+            CommandArgument::Positioned(0, string("song")),
+            CommandArgument::Positioned(1, CommandArgumentValue::I64(200)),
+        ]),
+    ];
+    assert_eq!(
+        registry.parse(&fragments),
+        Err(CommandParseError::ValidationFailed {
+            argument: "volume",
+            reason: "must be at most 100".to_string(),
+        })
+    );
+
+    assert_eq!(
+        registry
+            .specs()
+            .map(|spec| spec.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["music", "fun"]
+    );
+    assert!(registry.unregister("music").is_some());
+    assert_eq!(registry.get("music"), None);
+}
+
+#[test]
+fn register_dyn_command_replaces_aliases() {
+    let mut registry = DynCommandRegistry::new();
+    let first = DynCommandSpec::builder("first").alias("x").build().unwrap();
+    let second = DynCommandSpec::builder("second").build().unwrap();
+    assert_eq!(registry.register(first.clone()), vec![]);
+    assert_eq!(registry.register(second.clone()), vec![]);
+
+    let x = DynCommandSpec::builder("x")
+        .alias("second")
+        .build()
+        .unwrap();
+    assert_eq!(registry.register(x.clone()), vec![first, second]);
+    assert_eq!(registry.get("x"), Some(&x));
+    assert_eq!(registry.get("first"), None);
+    assert_eq!(registry.specs().collect::<Vec<_>>(), vec![&x]);
+}
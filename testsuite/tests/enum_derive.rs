@@ -19,18 +19,36 @@ fn basic_enum() {
         CommandSpec {
             name: "basic",
             description: "basic struct",
+            aliases: vec![],
             options: vec![],
+            exclusive: vec![],
+            permissions: Default::default(),
+            cooldown: None,
+            confirm: None,
+            self_execution: Default::default(),
             subcommands: vec![
                 CommandSpec {
                     name: "a",
                     description: "a",
+                    aliases: vec![],
                     options: vec![],
+                    exclusive: vec![],
+                    permissions: Default::default(),
+                    cooldown: None,
+                    confirm: None,
+                    self_execution: Default::default(),
                     subcommands: vec![],
                 },
                 CommandSpec {
                     name: "b",
                     description: "b",
+                    aliases: vec![],
                     options: vec![],
+                    exclusive: vec![],
+                    permissions: Default::default(),
+                    cooldown: None,
+                    confirm: None,
+                    self_execution: Default::default(),
                     subcommands: vec![],
                 },
             ],
@@ -58,18 +76,36 @@ fn struct_in_enum() {
         CommandSpec {
             name: "test",
             description: "test struct",
+            aliases: vec![],
             options: vec![],
+            exclusive: vec![],
+            permissions: Default::default(),
+            cooldown: None,
+            confirm: None,
+            self_execution: Default::default(),
             subcommands: vec![
                 CommandSpec {
                     name: "a",
                     description: "a",
+                    aliases: vec![],
                     options: vec![],
+                    exclusive: vec![],
+                    permissions: Default::default(),
+                    cooldown: None,
+                    confirm: None,
+                    self_execution: Default::default(),
                     subcommands: vec![],
                 },
                 CommandSpec {
                     name: "b",
                     description: "b",
+                    aliases: vec![],
                     options: vec![],
+                    exclusive: vec![],
+                    permissions: Default::default(),
+                    cooldown: None,
+                    confirm: None,
+                    self_execution: Default::default(),
                     subcommands: vec![],
                 },
             ],
@@ -104,28 +140,56 @@ fn struct_style_enum() {
         CommandSpec {
             name: "test",
             description: "test struct",
+            aliases: vec![],
             options: vec![],
+            exclusive: vec![],
+            permissions: Default::default(),
+            cooldown: None,
+            confirm: None,
+            self_execution: Default::default(),
             subcommands: vec![
                 CommandSpec {
                     name: "a",
                     description: "a",
+                    aliases: vec![],
                     options: vec![CommandOption {
                         name: "s",
                         position: 0,
                         description: "s",
                         value: CommandOptionValueKind::String,
+                        take_rest: false,
+                        variadic: false,
+                        validation: Default::default(),
+                        requires: vec![],
+                        completion_provider: None,
                     },],
+                    exclusive: vec![],
+                    permissions: Default::default(),
+                    cooldown: None,
+                    confirm: None,
+                    self_execution: Default::default(),
                     subcommands: vec![],
                 },
                 CommandSpec {
                     name: "b",
                     description: "b",
+                    aliases: vec![],
                     options: vec![CommandOption {
                         name: "s",
                         position: 0,
                         description: "s",
                         value: CommandOptionValueKind::String,
+                        take_rest: false,
+                        variadic: false,
+                        validation: Default::default(),
+                        requires: vec![],
+                        completion_provider: None,
                     },],
+                    exclusive: vec![],
+                    permissions: Default::default(),
+                    cooldown: None,
+                    confirm: None,
+                    self_execution: Default::default(),
                     subcommands: vec![],
                 },
             ],
@@ -0,0 +1,110 @@
+use kal::{
+    lex::{
+        CommandLexer, TokenTransformer, TransformHint, TransformHintPart, TransformHintProvider,
+    },
+    Arguments, Command, CommandArgument, CommandArgumentValue, CommandFragment, CommandOption,
+    CommandOptionValueKind, CommandParseError, ToFragments,
+};
+use pretty_assertions::assert_eq;
+
+/// shared moderation arguments
+#[derive(Debug, PartialEq, Arguments)]
+struct Moderation {
+    /// the target
+    target: String,
+    /// the reason
+    reason: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Command, TransformHintProvider)]
+#[command(to_fragments)]
+struct Warn {
+    /// the count
+    count: i64,
+    #[argument(flatten)]
+    moderation: Moderation,
+    /// the note
+    #[argument(take_rest)]
+    note: Option<String>,
+}
+
+#[test]
+fn flatten_spec() {
+    assert_eq!(Moderation::COUNT, 2);
+    let option = |name, position, description, value| CommandOption {
+        name,
+        position,
+        description,
+        value,
+        take_rest: false,
+        variadic: false,
+        validation: Default::default(),
+        requires: vec![],
+        completion_provider: None,
+    };
+    let optional_string =
+        || CommandOptionValueKind::Optional(Box::new(CommandOptionValueKind::String));
+    assert_eq!(
+        Warn::spec().options,
+        vec![
+            option("count", 0, "the count", CommandOptionValueKind::Integer),
+            option("target", 1, "the target", CommandOptionValueKind::String),
+            option("reason", 2, "the reason", optional_string()),
+            CommandOption {
+                take_rest: true,
+                ..option("note", 3, "the note", optional_string())
+            },
+        ]
+    );
+    assert_eq!(
+        Warn::hint(),
+        TransformHint::Execute(vec![
+            ("count", TransformHintPart::Integer),
+            ("target", TransformHintPart::String),
+            ("reason", TransformHintPart::String),
+            ("note", TransformHintPart::StringGreedy),
+        ])
+    );
+}
+
+#[test]
+fn flatten_parse() {
+    let transformer = TokenTransformer::command_args(Warn::hint());
+    for (command, reason) in [
+        ("3 alice spam too much", Some("spam")),
+        ("3 target=alice", None),
+        ("count=3 reason=spam target=alice", Some("spam")),
+    ] {
+        let lexer = CommandLexer::new(command);
+        let fragments: Result<Vec<_>, _> = transformer.transform(lexer).collect();
+        let parsed = fragments
+            .as_ref()
+            .map_err(CommandParseError::from)
+            .and_then(|fragments| Warn::parse(fragments));
+        let expected = Warn {
+            count: 3,
+            moderation: Moderation {
+                target: "alice".to_string(),
+                reason: reason.map(str::to_string),
+            },
+            note: reason
+                .filter(|_| command.ends_with("much"))
+                .map(|_| "too much".to_string()),
+        };
+        assert_eq!(Ok(&expected), parsed.as_ref(), "{}", command);
+        assert_eq!(
+            Ok(&expected),
+            Warn::parse(&expected.to_fragments()).as_ref(),
+            "{}",
+            command
+        );
+    }
+
+    assert_eq!(
+        Err(CommandParseError::MissingArguments(vec!["target"])),
+        Warn::parse(&[CommandFragment::Execute(vec![CommandArgument::Positioned(
+            0,
+            CommandArgumentValue::I64(3)
+        )])])
+    );
+}
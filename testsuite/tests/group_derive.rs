@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use kal::{
+    lex::{TransformHint, TransformHintPart, TransformHintProvider},
+    Command, CommandArgument, CommandArgumentValue, CommandFragment, CommandGroup,
+    CommandParseError, CommandSpec, ToFragments,
+};
+use pretty_assertions::assert_eq;
+
+/// ping
+#[derive(Debug, PartialEq, Command, TransformHintProvider)]
+#[command(to_fragments)]
+struct Ping;
+
+/// echo
+#[derive(Debug, PartialEq, Command)]
+#[command(to_fragments)]
+struct Echo {
+    #[argument(take_rest)]
+    text: String,
+}
+
+/// admin commands
+#[derive(Debug, PartialEq, CommandGroup)]
+#[command(to_fragments)]
+enum Admin {
+    #[command(hint_provider)]
+    Ping(Ping),
+    #[command(rename = "say")]
+    Echo(Echo),
+}
+
+/// the root
+#[derive(Debug, PartialEq, CommandGroup)]
+#[command(rename = "bot")]
+#[command(to_fragments)]
+enum Root {
+    /// check the bot
+    #[command(alias = "p", alias = "pong")]
+    Ping(Ping),
+    #[command(description = "bot administration")]
+    Admin(Admin),
+}
+
+#[test]
+fn group_spec() {
+    assert_eq!(Root::NAME, "bot");
+    assert_eq!(
+        Admin::spec(),
+        CommandSpec {
+            name: "admin",
+            description: "admin commands",
+            aliases: vec![],
+            options: vec![],
+            exclusive: vec![],
+            permissions: Default::default(),
+            cooldown: None,
+            confirm: None,
+            self_execution: Default::default(),
+            subcommands: vec![
+                Ping::spec(),
+                CommandSpec {
+                    name: "say",
+                    ..Echo::spec()
+                },
+            ],
+        }
+    );
+    assert_eq!(
+        Root::spec(),
+        CommandSpec {
+            name: "bot",
+            description: "the root",
+            aliases: vec![],
+            options: vec![],
+            exclusive: vec![],
+            permissions: Default::default(),
+            cooldown: None,
+            confirm: None,
+            self_execution: Default::default(),
+            subcommands: vec![
+                CommandSpec {
+                    name: "ping",
+                    description: "check the bot",
+                    aliases: vec!["p", "pong"],
+                    options: vec![],
+                    exclusive: vec![],
+                    permissions: Default::default(),
+                    cooldown: None,
+                    confirm: None,
+                    self_execution: Default::default(),
+                    subcommands: vec![],
+                },
+                CommandSpec {
+                    description: "bot administration",
+                    ..Admin::spec()
+                },
+            ],
+        }
+    );
+}
+
+#[test]
+fn group_parse() {
+    let execute = |arguments| CommandFragment::Execute(arguments);
+    let select = |name: &str| CommandFragment::Select(name.to_string());
+
+    for name in ["ping", "p", "pong"] {
+        assert_eq!(
+            Ok(Root::Ping(Ping)),
+            Root::parse(&[select(name), execute(vec![])])
+        );
+    }
+    let fragments = [
+        select("admin"),
+        select("say"),
+        execute(vec![CommandArgument::Positioned(
+            0,
+            CommandArgumentValue::String("hello".to_string()),
+        )]),
+    ];
+    let parsed = Root::Admin(Admin::Echo(Echo {
+        text: "hello".to_string(),
+    }));
+    assert_eq!(Ok(&parsed), Root::parse(&fragments).as_ref());
+    assert_eq!(parsed.to_fragments(), fragments);
+
+    let unknown = "echo".to_string();
+    assert_eq!(
+        Err(CommandParseError::UnknownCommand(&unknown)),
+        Root::parse(&[select("admin"), select("echo"), execute(vec![])])
+    );
+}
+
+#[test]
+fn group_hint() {
+    let admin = TransformHint::Select(HashMap::from_iter([
+        ("ping", TransformHint::Execute(vec![])),
+        (
+            "say",
+            TransformHint::Execute(vec![("text", TransformHintPart::StringGreedy)]),
+        ),
+    ]));
+    assert_eq!(Admin::hint(), admin);
+    assert_eq!(
+        Root::hint(),
+        TransformHint::Select(HashMap::from_iter([
+            ("ping", TransformHint::Execute(vec![])),
+            ("p", TransformHint::Execute(vec![])),
+            ("pong", TransformHint::Execute(vec![])),
+            ("admin", admin),
+        ]))
+    );
+    assert_eq!(Root::hint(), TransformHint::from(Root::spec_ref()));
+}
+
+#[test]
+fn command_group_to_fragments() {
+    kal::command_group! {
+        #[command(to_fragments)]
+        #[derive(Debug, PartialEq)]
+        enum Legacy {
+            #[hint_provider]
+            Ping(Ping),
+            Echo(Echo),
+        }
+    }
+
+    let parsed = Legacy::Echo(Echo {
+        text: "hello".to_string(),
+    });
+    let fragments = parsed.to_fragments();
+    assert_eq!(
+        fragments,
+        vec![
+            CommandFragment::Select("echo".to_string()),
+            CommandFragment::Execute(vec![CommandArgument::Positioned(
+                0,
+                CommandArgumentValue::String("hello".to_string()),
+            )]),
+        ]
+    );
+    assert_eq!(Legacy::parse(&fragments), Ok(parsed));
+    assert_eq!(
+        Legacy::Ping(Ping).to_fragments()[0],
+        CommandFragment::Select("ping".to_string())
+    );
+}
@@ -1,4 +1,6 @@
-use kal::lex::{CommandLexError, CommandLexer, CommandToken, RawStringPattern};
+use kal::lex::{
+    lex_all, CommandLexError, CommandLexer, CommandLexerOptions, CommandToken, RawStringPattern,
+};
 use pretty_assertions::assert_eq;
 
 #[test]
@@ -38,6 +40,18 @@ fn lex() {
 fn lex_quote() {
     for (src, result) in [
         ("\"what", Err(CommandLexError::UnclosedQuote(0, "\"what"))),
+        (
+            "\"\"",
+            Ok(vec![CommandToken::QuotedString("\"", "".to_string(), "\"")]),
+        ),
+        (
+            r#""\"leading""#,
+            Ok(vec![CommandToken::QuotedString(
+                "\"",
+                "\"leading".to_string(),
+                "\"",
+            )]),
+        ),
         (
             "\"ok\"",
             Ok(vec![CommandToken::QuotedString(
@@ -96,3 +110,85 @@ fn lex_named() {
         assert_eq!(result, tokens);
     }
 }
+
+#[test]
+fn lex_numeric() {
+    for (src, pattern) in [
+        ("1_000", RawStringPattern::SeparatedInteger),
+        ("-1_000.5", RawStringPattern::SeparatedFloat),
+        ("1,000", RawStringPattern::Unrecognized),
+        ("1e5", RawStringPattern::Scientific),
+        ("+2.5E-3", RawStringPattern::Scientific),
+        ("1e", RawStringPattern::Unrecognized),
+        ("0xff", RawStringPattern::Hexadecimal),
+        ("-0o17", RawStringPattern::Octal),
+        ("0b1010_1010", RawStringPattern::Binary),
+        ("0xfg", RawStringPattern::Unrecognized),
+        ("1__000", RawStringPattern::Unrecognized),
+        ("inf", RawStringPattern::Unrecognized),
+        ("-", RawStringPattern::Unrecognized),
+    ] {
+        let tokens: Result<Vec<_>, _> = CommandLexer::new(src).collect();
+        assert_eq!(Ok(vec![CommandToken::RawString(src, pattern)]), tokens);
+    }
+}
+
+#[test]
+fn lex_numeric_opt_in() {
+    let options = CommandLexerOptions {
+        comma_digit_separator: true,
+        special_floats: true,
+        ..CommandLexerOptions::default()
+    };
+    for (src, pattern) in [
+        ("1,000", RawStringPattern::SeparatedInteger),
+        ("12,345,678.9", RawStringPattern::SeparatedFloat),
+        ("1,2,3", RawStringPattern::Unrecognized),
+        ("-inf", RawStringPattern::Infinity),
+        ("Infinity", RawStringPattern::Infinity),
+        ("NaN", RawStringPattern::NaN),
+    ] {
+        let tokens: Result<Vec<_>, _> = CommandLexer::with_options(src, options.clone()).collect();
+        assert_eq!(Ok(vec![CommandToken::RawString(src, pattern)]), tokens);
+    }
+}
+
+#[test]
+fn lex_recover() {
+    let (tokens, errors) = lex_all(r#"ban a==b "reason here x=y=z ok"#);
+    assert_eq!(
+        vec![
+            CommandToken::RawString("ban", RawStringPattern::Unrecognized),
+            CommandToken::Whitespace(" "),
+            CommandToken::Whitespace(" "),
+            CommandToken::Whitespace(" "),
+            CommandToken::RawString("here", RawStringPattern::Unrecognized),
+            CommandToken::Whitespace(" "),
+            CommandToken::Whitespace(" "),
+            CommandToken::RawString("ok", RawStringPattern::Unrecognized),
+        ],
+        tokens
+    );
+    assert_eq!(
+        vec![
+            CommandLexError::NamedCannotContainNamed(4, "a==b"),
+            CommandLexError::UnclosedQuote(9, "\"reason"),
+            CommandLexError::NamedCannotContainNamed(22, "x=y=z"),
+        ],
+        errors
+    );
+
+    let recovering = CommandLexerOptions {
+        recover: true,
+        ..CommandLexerOptions::default()
+    };
+    let tokens: Vec<_> = CommandLexer::with_options("a= 1", recovering).collect();
+    assert_eq!(
+        vec![
+            Err(CommandLexError::NamedProhibitsWhitespace(0, "a=")),
+            Ok(CommandToken::Whitespace(" ")),
+            Ok(CommandToken::RawString("1", RawStringPattern::Integer)),
+        ],
+        tokens
+    );
+}
@@ -0,0 +1,225 @@
+use std::{any::Any, ops::ControlFlow, sync::Mutex};
+
+use kal::{
+    dispatch::{BoxFuture, Dispatcher, Handler, Middleware, Next},
+    Command, CommandFragment, CommandParseError, CommandSpec,
+};
+use pretty_assertions::assert_eq;
+
+#[derive(Debug, PartialEq, Command)]
+#[command(to_fragments)]
+enum Role {
+    Add { name: String },
+    Remove { name: String },
+}
+
+#[derive(Default)]
+struct Context {
+    log: Mutex<Vec<String>>,
+}
+
+impl Handler<Context> for Role {
+    type Output = String;
+
+    async fn handle(self, _ctx: &Context) -> String {
+        format!("{:?}", self)
+    }
+}
+
+struct Logger;
+
+impl Middleware<Context, String> for Logger {
+    fn before_parse<'a>(
+        &'a self,
+        fragments: &'a [CommandFragment],
+        ctx: &'a Context,
+    ) -> BoxFuture<'a, ControlFlow<String>> {
+        ctx.log
+            .lock()
+            .unwrap()
+            .push(format!("before {}", fragments.len()));
+        Box::pin(async { ControlFlow::Continue(()) })
+    }
+
+    fn after_parse<'a>(
+        &'a self,
+        path: &'a [&'static CommandSpec],
+        _command: &'a (dyn Any + Send + Sync),
+        ctx: &'a Context,
+    ) -> BoxFuture<'a, ControlFlow<String>> {
+        let path: Vec<_> = path.iter().map(|spec| spec.name).collect();
+        ctx.log
+            .lock()
+            .unwrap()
+            .push(format!("after {}", path.join(" ")));
+        Box::pin(async { ControlFlow::Continue(()) })
+    }
+}
+
+struct ProtectAdmin;
+
+impl Middleware<Context, String> for ProtectAdmin {
+    fn after_parse<'a>(
+        &'a self,
+        _path: &'a [&'static CommandSpec],
+        command: &'a (dyn Any + Send + Sync),
+        _ctx: &'a Context,
+    ) -> BoxFuture<'a, ControlFlow<String>> {
+        Box::pin(async move {
+            match command.downcast_ref::<Role>() {
+                Some(Role::Remove { name }) if name == "admin" => {
+                    ControlFlow::Break("admin is protected".to_string())
+                }
+                _ => ControlFlow::Continue(()),
+            }
+        })
+    }
+
+    fn on_error<'a>(
+        &'a self,
+        error: &'a CommandParseError<'a>,
+        _ctx: &'a Context,
+    ) -> BoxFuture<'a, Option<String>> {
+        Box::pin(async move {
+            match error {
+                CommandParseError::UnknownCommand(_) => None,
+                error => Some(format!("error: {}", error)),
+            }
+        })
+    }
+}
+
+/// Wrap the handler, logging around it and tagging its output.
+struct Wrap(&'static str);
+
+impl Middleware<Context, String> for Wrap {
+    fn around_handle<'a>(
+        &'a self,
+        _path: &'a [&'static CommandSpec],
+        ctx: &'a Context,
+        next: Next<'a, String>,
+    ) -> BoxFuture<'a, String> {
+        Box::pin(async move {
+            ctx.log.lock().unwrap().push(format!("enter {}", self.0));
+            let output = next.run().await;
+            ctx.log.lock().unwrap().push(format!("leave {}", self.0));
+            format!("{} [{}]", output, self.0)
+        })
+    }
+}
+
+/// Skip the handler for the commands removing anyone.
+struct ReadOnly;
+
+impl Middleware<Context, String> for ReadOnly {
+    fn around_handle<'a>(
+        &'a self,
+        path: &'a [&'static CommandSpec],
+        _ctx: &'a Context,
+        next: Next<'a, String>,
+    ) -> BoxFuture<'a, String> {
+        match path.last() {
+            Some(spec) if spec.name == "remove" => Box::pin(async { "read only".to_string() }),
+            _ => next.run(),
+        }
+    }
+}
+
+fn fragments(subcommand: &str, name: Option<&str>) -> Vec<CommandFragment> {
+    vec![
+        CommandFragment::Select("role".to_string()),
+        CommandFragment::Select(subcommand.to_string()),
+        CommandFragment::Execute(
+            name.map(|name| {
+                kal::CommandArgument::Named(
+                    "name".to_string(),
+                    kal::CommandArgumentValue::String(name.to_string()),
+                )
+            })
+            .into_iter()
+            .collect(),
+        ),
+    ]
+}
+
+#[test]
+fn middleware_chain() {
+    let dispatcher = Dispatcher::new()
+        .route::<Role>()
+        .layer(Logger)
+        .layer(ProtectAdmin);
+    let ctx = Context::default();
+
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments("add", Some("mod")), &ctx)),
+        Ok(r#"Add { name: "mod" }"#.to_string())
+    );
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments("remove", Some("admin")), &ctx)),
+        Ok("admin is protected".to_string())
+    );
+    assert_eq!(
+        *ctx.log.lock().unwrap(),
+        vec![
+            "before 3",
+            "after role add",
+            "before 3",
+            "after role remove"
+        ]
+    );
+}
+
+#[test]
+fn middleware_on_error() {
+    let dispatcher = Dispatcher::new().route::<Role>().layer(ProtectAdmin);
+    let ctx = Context::default();
+
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments("add", None), &ctx)),
+        Ok("error: Missing arguments: name".to_string())
+    );
+
+    let unknown = vec![CommandFragment::Select("user".to_string())];
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&unknown, &ctx)),
+        Err(CommandParseError::UnknownCommand(&"user".to_string()))
+    );
+}
+
+#[test]
+fn middleware_around_handle() {
+    let dispatcher = Dispatcher::new()
+        .route::<Role>()
+        .layer(Wrap("outer"))
+        .layer(ProtectAdmin)
+        .layer(Wrap("inner"))
+        .layer(ReadOnly);
+    let ctx = Context::default();
+
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments("add", Some("mod")), &ctx)),
+        Ok(r#"Add { name: "mod" } [inner] [outer]"#.to_string())
+    );
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments("remove", Some("mod")), &ctx)),
+        Ok("read only [inner] [outer]".to_string())
+    );
+    // Stopped after the parse, the handler is not wrapped at all.
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments("remove", Some("admin")), &ctx)),
+        Ok("admin is protected".to_string())
+    );
+    assert_eq!(
+        *ctx.log.lock().unwrap(),
+        vec![
+            "enter outer",
+            "enter inner",
+            "leave inner",
+            "leave outer",
+            "enter outer",
+            "enter inner",
+            "leave inner",
+            "leave outer",
+        ]
+    );
+}
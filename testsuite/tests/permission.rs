@@ -0,0 +1,263 @@
+use kal::{
+    dispatch::{Dispatcher, Handler, RequirePermissions},
+    lex::TransformHintProvider,
+    Command, CommandFragment, CommandGroup, CommandPermissions, PermissionChecker,
+};
+use pretty_assertions::assert_eq;
+
+#[derive(Debug, Command, TransformHintProvider)]
+#[command(permissions = "ban_members")]
+#[command(to_fragments)]
+enum Ban {
+    Add {
+        user: String,
+    },
+    #[command(permissions = "manage_guild, view_audit_log")]
+    Purge,
+}
+
+#[derive(Debug, Command, TransformHintProvider)]
+#[command(owner_only)]
+struct Shutdown;
+
+#[derive(Debug, Command, TransformHintProvider)]
+#[command(to_fragments)]
+struct Ping;
+
+/// Executing `role` itself lists roles, which anyone can select `role info` beside.
+#[derive(Debug, Command, TransformHintProvider)]
+#[command(to_fragments)]
+enum Role {
+    #[command(self, permissions = "manage_roles")]
+    List {
+        filter: Option<String>,
+    },
+    Info {
+        name: String,
+    },
+}
+
+#[derive(CommandGroup)]
+#[allow(dead_code)]
+enum Root {
+    Ban(Ban),
+    #[command(permissions = "administrator")]
+    Shutdown(Shutdown),
+    Ping(Ping),
+}
+
+struct Member {
+    owner: bool,
+    permissions: Vec<&'static str>,
+}
+
+impl PermissionChecker for Member {
+    fn is_owner(&self) -> bool {
+        self.owner
+    }
+
+    fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+#[test]
+fn permission_spec() {
+    assert_eq!(
+        Ban::spec().permissions,
+        CommandPermissions {
+            required: vec!["ban_members"],
+            owner_only: false,
+        }
+    );
+    assert_eq!(
+        Ban::spec().subcommands[1].permissions,
+        CommandPermissions {
+            required: vec!["manage_guild", "view_audit_log"],
+            owner_only: false,
+        }
+    );
+    assert_eq!(
+        Shutdown::spec().permissions,
+        CommandPermissions {
+            required: vec![],
+            owner_only: true,
+        }
+    );
+    assert_eq!(
+        Root::spec().subcommands[1].permissions,
+        CommandPermissions {
+            required: vec!["administrator"],
+            owner_only: true,
+        }
+    );
+    assert_eq!(Ping::spec().permissions, CommandPermissions::default());
+}
+
+#[test]
+fn permission_visible_to() {
+    let names = |member: &Member| -> Vec<Vec<&'static str>> {
+        Root::spec()
+            .visible_to(member)
+            .map(|spec| {
+                spec.subcommands
+                    .iter()
+                    .map(|spec| {
+                        std::iter::once(spec.name)
+                            .chain(spec.subcommands.iter().map(|spec| spec.name))
+                            .collect()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    assert_eq!(
+        names(&Member {
+            owner: false,
+            permissions: vec![],
+        }),
+        vec![vec!["ping"]]
+    );
+    assert_eq!(
+        names(&Member {
+            owner: false,
+            permissions: vec!["ban_members"],
+        }),
+        vec![vec!["ban", "add"], vec!["ping"]]
+    );
+    assert_eq!(
+        names(&Member {
+            owner: true,
+            permissions: vec![
+                "ban_members",
+                "manage_guild",
+                "view_audit_log",
+                "administrator"
+            ],
+        }),
+        vec![vec!["ban", "add", "purge"], vec!["shutdown"], vec!["ping"]]
+    );
+}
+
+impl Handler<Member> for Ban {
+    type Output = String;
+
+    async fn handle(self, _ctx: &Member) -> String {
+        format!("{:?}", self)
+    }
+}
+
+impl Handler<Member> for Ping {
+    type Output = String;
+
+    async fn handle(self, _ctx: &Member) -> String {
+        "pong".to_string()
+    }
+}
+
+#[test]
+fn permission_dispatch() {
+    let dispatcher =
+        Dispatcher::new()
+            .route::<Ban>()
+            .route::<Ping>()
+            .layer(RequirePermissions::new(
+                |spec: &kal::CommandSpec, _: &Member| format!("{} is forbidden", spec.name),
+            ));
+    let fragments = vec![
+        CommandFragment::Select("ban".to_string()),
+        CommandFragment::Select("purge".to_string()),
+        CommandFragment::Execute(vec![]),
+    ];
+
+    let member = Member {
+        owner: false,
+        permissions: vec!["ban_members"],
+    };
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &member)),
+        Ok("purge is forbidden".to_string())
+    );
+    assert_eq!(
+        dispatcher
+            .visible_specs(&member)
+            .iter()
+            .map(|spec| spec.subcommands.len())
+            .collect::<Vec<_>>(),
+        vec![1, 0]
+    );
+
+    let member = Member {
+        owner: false,
+        permissions: vec!["ban_members", "manage_guild", "view_audit_log"],
+    };
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&fragments, &member)),
+        Ok("Purge".to_string())
+    );
+}
+
+impl Handler<Member> for Role {
+    type Output = String;
+
+    async fn handle(self, _ctx: &Member) -> String {
+        format!("{:?}", self)
+    }
+}
+
+#[test]
+fn permission_self_variant() {
+    assert_eq!(Role::spec().permissions, CommandPermissions::default());
+    assert_eq!(
+        Role::spec().self_execution.permissions,
+        CommandPermissions {
+            required: vec!["manage_roles"],
+            owner_only: false,
+        }
+    );
+
+    let dispatcher = Dispatcher::new()
+        .route::<Role>()
+        .layer(RequirePermissions::new(
+            |spec: &kal::CommandSpec, _: &Member| format!("{} is forbidden", spec.name),
+        ));
+    let list = vec![
+        CommandFragment::Select("role".to_string()),
+        CommandFragment::Execute(vec![]),
+    ];
+    let info = vec![
+        CommandFragment::Select("role".to_string()),
+        CommandFragment::Select("info".to_string()),
+        CommandFragment::Execute(vec![kal::CommandArgument::Positioned(
+            0,
+            kal::CommandArgumentValue::String("mod".to_string()),
+        )]),
+    ];
+
+    let member = Member {
+        owner: false,
+        permissions: vec![],
+    };
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&list, &member)),
+        Ok("role is forbidden".to_string())
+    );
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&info, &member)),
+        Ok(r#"Info { name: "mod" }"#.to_string())
+    );
+    let visible = Role::spec().visible_to(&member).unwrap();
+    assert_eq!(visible.options, vec![]);
+    assert_eq!(visible.subcommands, Role::spec().subcommands);
+
+    let member = Member {
+        owner: false,
+        permissions: vec!["manage_roles"],
+    };
+    assert_eq!(
+        pollster::block_on(dispatcher.dispatch(&list, &member)),
+        Ok("List { filter: None }".to_string())
+    );
+    assert_eq!(Role::spec().visible_to(&member), Some(Role::spec()));
+}
@@ -0,0 +1,172 @@
+use std::any::Any;
+
+use kal::{
+    Command, CommandAdapter, CommandArgument, CommandArgumentValue, CommandFragment,
+    CommandParseError, CommandPlugin, PluginRegistry,
+};
+use pretty_assertions::assert_eq;
+
+/// Ping the bot
+#[derive(Debug, PartialEq, Command)]
+#[command(rename = "ping")]
+struct Ping;
+
+/// Music player
+#[derive(Debug, PartialEq, Command)]
+enum Music {
+    /// Play a song
+    Play {
+        /// The song to play
+        song: String,
+    },
+    /// Stop playing
+    Stop,
+}
+
+#[derive(Debug, PartialEq)]
+enum Plugin {
+    Ping(Ping),
+    Music(Music),
+}
+
+fn play(song: &str) -> Vec<CommandFragment> {
+    vec![
+        CommandFragment::Select("music".to_string()),
+        CommandFragment::Select("play".to_string()),
+        CommandFragment::Execute(vec![CommandArgument::Positioned(
+            0,
+            CommandArgumentValue::String(song.to_string()),
+        )]),
+    ]
+}
+
+#[test]
+fn plugins_as_trait_objects() {
+    let plugins: Vec<Box<dyn CommandPlugin>> = vec![
+        Box::new(CommandAdapter::<Ping>::new()),
+        Box::new(CommandAdapter::<Music>::new()),
+    ];
+    assert_eq!(
+        plugins
+            .iter()
+            .map(|plugin| plugin.name())
+            .collect::<Vec<_>>(),
+        vec!["ping", "music"]
+    );
+    assert_eq!(plugins[1].spec(), Music::spec_ref());
+
+    let fragments = play("song");
+    let command: Box<dyn Any + Send + Sync> = plugins[1].parse(&fragments[1..]).unwrap();
+    assert_eq!(
+        command.downcast_ref::<Music>(),
+        Some(&Music::Play {
+            song: "song".to_string()
+        })
+    );
+}
+
+#[test]
+fn registry_with_enum() {
+    let mut registry = PluginRegistry::new("bot", "The bot");
+    registry.register(CommandAdapter::map(Plugin::Ping));
+    registry.register(CommandAdapter::map(Plugin::Music));
+
+    let spec = registry.spec();
+    assert_eq!(spec.name, "bot");
+    assert_eq!(spec.description, "The bot");
+    assert_eq!(
+        spec.subcommands,
+        vec![Ping::spec_ref().clone(), Music::spec_ref().clone()]
+    );
+
+    assert_eq!(
+        registry.parse(&play("song")),
+        Ok(Plugin::Music(Music::Play {
+            song: "song".to_string()
+        }))
+    );
+    assert_eq!(
+        registry.parse(&[
+            CommandFragment::Select("ping".to_string()),
+            CommandFragment::Execute(vec![]),
+        ]),
+        Ok(Plugin::Ping(Ping))
+    );
+
+    let fragments = [CommandFragment::Select("weather".to_string())];
+    assert_eq!(
+        registry.parse(&fragments),
+        Err(CommandParseError::UnknownCommand(&"weather".to_string()))
+    );
+    assert_eq!(
+        registry.parse(&[]),
+        Err(CommandParseError::IncompleteCommand)
+    );
+}
+
+#[test]
+fn registry_replaces_same_name() {
+    let mut registry: PluginRegistry = PluginRegistry::new("bot", "The bot");
+    registry.register(CommandAdapter::<Music>::new());
+    registry.register_boxed(Box::new(CommandAdapter::<Ping>::new()));
+    registry.register(CommandAdapter::<Music>::new());
+
+    assert_eq!(
+        registry
+            .plugins()
+            .map(|plugin| plugin.name())
+            .collect::<Vec<_>>(),
+        vec!["ping", "music"]
+    );
+    let command = registry.parse(&play("song")).unwrap();
+    assert!(command.is::<Music>());
+}
+
+/// The plugin for `pong`, which is also selected by `ping`.
+struct Pong;
+
+impl CommandPlugin for Pong {
+    fn name(&self) -> &'static str {
+        "pong"
+    }
+
+    fn spec(&self) -> &'static kal::CommandSpec {
+        static SPEC: std::sync::OnceLock<kal::CommandSpec> = std::sync::OnceLock::new();
+        SPEC.get_or_init(|| kal::CommandSpec {
+            name: "pong",
+            aliases: vec!["ping"],
+            ..Ping::spec()
+        })
+    }
+
+    fn parse<'a>(
+        &self,
+        fragments: &'a [CommandFragment],
+    ) -> Result<Box<dyn Any + Send + Sync>, CommandParseError<'a>> {
+        Ok(Box::new(Ping::parse(fragments)?))
+    }
+}
+
+#[test]
+fn registry_replaces_same_alias() {
+    let mut registry: PluginRegistry = PluginRegistry::new("bot", "The bot");
+    registry.register(CommandAdapter::<Ping>::new());
+    registry.register(CommandAdapter::<Music>::new());
+    registry.register(Pong);
+    assert_eq!(
+        registry
+            .plugins()
+            .map(|plugin| plugin.name())
+            .collect::<Vec<_>>(),
+        vec!["music", "pong"]
+    );
+
+    registry.register(CommandAdapter::<Ping>::new());
+    assert_eq!(
+        registry
+            .plugins()
+            .map(|plugin| plugin.name())
+            .collect::<Vec<_>>(),
+        vec!["music", "ping"]
+    );
+}
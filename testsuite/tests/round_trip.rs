@@ -0,0 +1,151 @@
+use kal::{
+    lex::{
+        render, CommandLexer, CommandLexerOptions, RenderStyle, TokenTransformer,
+        TransformHintProvider,
+    },
+    Command, CommandArgument, CommandArgumentValue, CommandFragment, CommandParseError,
+    ToFragments,
+};
+use pretty_assertions::assert_eq;
+use proptest::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Command, TransformHintProvider)]
+#[command(to_fragments)]
+enum Remind {
+    At {
+        minutes: i64,
+        message: String,
+    },
+    Scale {
+        factor: f64,
+        label: Option<String>,
+    },
+    Note {
+        #[argument(take_rest)]
+        text: String,
+    },
+    Clear,
+}
+
+#[derive(Clone, Debug, PartialEq, Command, TransformHintProvider)]
+#[command(to_fragments)]
+enum Root {
+    Remind(Remind),
+    Ping,
+}
+
+fn round_trip<T: ToFragments + TransformHintProvider>(value: &T) -> (String, T) {
+    let hint = T::hint();
+    let rendered = render(
+        &value.to_fragments(),
+        RenderStyle {
+            hint: Some(&hint),
+            ..RenderStyle::default()
+        },
+    );
+
+    let transformer = TokenTransformer::command_args(hint.clone());
+    let fragments: Result<Vec<_>, _> = transformer
+        .transform(CommandLexer::with_options(
+            &rendered,
+            CommandLexerOptions {
+                special_floats: true,
+                ..CommandLexerOptions::default()
+            },
+        ))
+        .collect();
+    let parsed = fragments
+        .as_ref()
+        .map_err(CommandParseError::from)
+        .and_then(|fragments| T::parse(fragments))
+        .unwrap_or_else(|e| panic!("failed to parse back {:?}: {}", rendered, e));
+    (rendered, parsed)
+}
+
+#[test]
+fn to_fragments() {
+    assert_eq!(
+        vec![
+            CommandFragment::Select("remind".to_string()),
+            CommandFragment::Select("scale".to_string()),
+            CommandFragment::Execute(vec![CommandArgument::Positioned(
+                0,
+                CommandArgumentValue::F64(1.5)
+            )]),
+        ],
+        Root::Remind(Remind::Scale {
+            factor: 1.5,
+            label: None,
+        })
+        .to_fragments()
+    );
+}
+
+#[test]
+fn render_command() {
+    let fragments = vec![
+        CommandFragment::Select("remind".to_string()),
+        CommandFragment::Execute(vec![
+            CommandArgument::Positioned(0, CommandArgumentValue::I64(5)),
+            CommandArgument::Positioned(1, CommandArgumentValue::String("buy milk".to_string())),
+            CommandArgument::Named(
+                "note".to_string(),
+                CommandArgumentValue::String(r#"say "hi" \o/"#.to_string()),
+            ),
+        ]),
+    ];
+    assert_eq!(
+        r#"/remind 5 "buy milk" note="say \"hi\" \\o/""#,
+        render(
+            &fragments,
+            RenderStyle {
+                label_prefix: "/",
+                ..RenderStyle::default()
+            }
+        )
+    );
+}
+
+#[test]
+fn round_trip_unit() {
+    assert_eq!(Root::Ping, round_trip(&Root::Ping).1);
+    assert_eq!(
+        Root::Remind(Remind::Clear),
+        round_trip(&Root::Remind(Remind::Clear)).1
+    );
+}
+
+fn remind() -> impl Strategy<Value = Remind> {
+    prop_oneof![
+        (any::<i64>(), any::<String>())
+            .prop_map(|(minutes, message)| Remind::At { minutes, message }),
+        (
+            prop_oneof![
+                proptest::num::f64::NORMAL,
+                proptest::num::f64::SUBNORMAL,
+                proptest::num::f64::ZERO,
+                proptest::num::f64::INFINITE,
+                proptest::num::f64::QUIET_NAN
+            ],
+            any::<Option<String>>()
+        )
+            .prop_map(|(factor, label)| Remind::Scale { factor, label }),
+        "[a-z][a-z0-9= ]{0,20}".prop_map(|text| Remind::Note { text }),
+        Just(Remind::Clear),
+    ]
+}
+
+// NaN is never equal to itself, so compare what the values print instead.
+proptest! {
+    #[test]
+    fn round_trip_remind(value in remind()) {
+        let (rendered, parsed) = round_trip(&value);
+        prop_assert_eq!(format!("{:?}", value), format!("{:?}", parsed), "rendered as {:?}", rendered);
+    }
+
+    #[test]
+    fn round_trip_root(value in remind().prop_map(Root::Remind)) {
+        let (rendered, parsed) = round_trip(&value);
+        prop_assert_eq!(format!("{:?}", value), format!("{:?}", parsed), "rendered as {:?}", rendered);
+    }
+}
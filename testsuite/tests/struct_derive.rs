@@ -12,7 +12,13 @@ fn basic_struct() {
         CommandSpec {
             name: "basic",
             description: "basic struct",
+            aliases: vec![],
             options: vec![],
+            exclusive: vec![],
+            permissions: Default::default(),
+            cooldown: None,
+            confirm: None,
+            self_execution: Default::default(),
             subcommands: vec![],
         }
     );
@@ -39,26 +45,47 @@ fn required_arguments() {
         CommandSpec {
             name: "test",
             description: "test struct",
+            aliases: vec![],
             options: vec![
                 CommandOption {
                     name: "s",
                     position: 0,
                     description: "String",
                     value: CommandOptionValueKind::String,
+                    take_rest: false,
+                    variadic: false,
+                    validation: Default::default(),
+                    requires: vec![],
+                    completion_provider: None,
                 },
                 CommandOption {
                     name: "i",
                     position: 1,
                     description: "i64",
                     value: CommandOptionValueKind::Integer,
+                    take_rest: false,
+                    variadic: false,
+                    validation: Default::default(),
+                    requires: vec![],
+                    completion_provider: None,
                 },
                 CommandOption {
                     name: "f",
                     position: 2,
                     description: "f64",
                     value: CommandOptionValueKind::Double,
+                    take_rest: false,
+                    variadic: false,
+                    validation: Default::default(),
+                    requires: vec![],
+                    completion_provider: None,
                 }
             ],
+            exclusive: vec![],
+            permissions: Default::default(),
+            cooldown: None,
+            confirm: None,
+            self_execution: Default::default(),
             subcommands: vec![],
         }
     );
@@ -85,6 +112,7 @@ fn optional_arguments() {
         CommandSpec {
             name: "test",
             description: "test struct",
+            aliases: vec![],
             options: vec![
                 CommandOption {
                     name: "s",
@@ -93,6 +121,11 @@ fn optional_arguments() {
                     value: CommandOptionValueKind::Optional(Box::new(
                         CommandOptionValueKind::String
                     )),
+                    take_rest: false,
+                    variadic: false,
+                    validation: Default::default(),
+                    requires: vec![],
+                    completion_provider: None,
                 },
                 CommandOption {
                     name: "i",
@@ -101,6 +134,11 @@ fn optional_arguments() {
                     value: CommandOptionValueKind::Optional(Box::new(
                         CommandOptionValueKind::Integer
                     )),
+                    take_rest: false,
+                    variadic: false,
+                    validation: Default::default(),
+                    requires: vec![],
+                    completion_provider: None,
                 },
                 CommandOption {
                     name: "f",
@@ -109,9 +147,85 @@ fn optional_arguments() {
                     value: CommandOptionValueKind::Optional(Box::new(
                         CommandOptionValueKind::Double
                     )),
+                    take_rest: false,
+                    variadic: false,
+                    validation: Default::default(),
+                    requires: vec![],
+                    completion_provider: None,
                 }
             ],
+            exclusive: vec![],
+            permissions: Default::default(),
+            cooldown: None,
+            confirm: None,
+            self_execution: Default::default(),
             subcommands: vec![],
         }
     );
 }
+
+#[test]
+fn cached_spec() {
+    /// test struct
+    #[derive(Command)]
+    #[allow(dead_code)]
+    struct Test {
+        /// String
+        s: String,
+    }
+
+    assert_eq!(&Test::spec(), Test::spec_ref());
+    assert!(std::ptr::eq(Test::spec_ref(), Test::spec_ref()));
+}
+
+#[test]
+fn cached_spec_default() {
+    /// Written by hand, relying on the default `spec_ref`.
+    struct Manual;
+
+    impl Command for Manual {
+        const NAME: &'static str = "manual";
+
+        fn spec() -> CommandSpec {
+            CommandSpec {
+                name: Self::NAME,
+                description: "manual",
+                aliases: vec![],
+                options: vec![],
+                exclusive: vec![],
+                permissions: Default::default(),
+                cooldown: None,
+                confirm: None,
+                self_execution: Default::default(),
+                subcommands: vec![],
+            }
+        }
+
+        fn parse(_fragments: &[kal::CommandFragment]) -> Result<Self, kal::CommandParseError<'_>> {
+            Ok(Manual)
+        }
+    }
+
+    /// Another one, to check the cache tells the types apart.
+    struct Other;
+
+    impl Command for Other {
+        const NAME: &'static str = "other";
+
+        fn spec() -> CommandSpec {
+            CommandSpec {
+                name: Self::NAME,
+                ..Manual::spec()
+            }
+        }
+
+        fn parse(_fragments: &[kal::CommandFragment]) -> Result<Self, kal::CommandParseError<'_>> {
+            Ok(Other)
+        }
+    }
+
+    assert_eq!(&Manual::spec(), Manual::spec_ref());
+    assert!(std::ptr::eq(Manual::spec_ref(), Manual::spec_ref()));
+    assert_eq!(Other::spec_ref().name, "other");
+    assert_eq!(Manual::spec_ref().name, "manual");
+}
@@ -2,8 +2,8 @@ use std::{collections::HashMap, iter::FromIterator};
 
 use kal::{
     lex::{
-        remove_leading, remove_trailing, CommandLexer, TokenTransformer, TransformHint,
-        TransformHintPart,
+        remove_leading, remove_trailing, split_commands, CommandLexer, TokenTransformError,
+        TokenTransformer, TransformHint, TransformHintPart,
     },
     CommandArgument, CommandArgumentValue, CommandFragment,
 };
@@ -44,19 +44,19 @@ fn transform_argument() {
             HashMap::from_iter([
                 (
                     "int",
-                    TransformHint::Execute(vec![TransformHintPart::Integer]),
+                    TransformHint::Execute(vec![("i", TransformHintPart::Integer)]),
                 ),
                 (
                     "float",
-                    TransformHint::Execute(vec![TransformHintPart::Float]),
+                    TransformHint::Execute(vec![("f", TransformHintPart::Float)]),
                 ),
                 (
                     "string",
-                    TransformHint::Execute(vec![TransformHintPart::String]),
+                    TransformHint::Execute(vec![("s", TransformHintPart::String)]),
                 ),
                 (
                     "greedy",
-                    TransformHint::Execute(vec![TransformHintPart::StringGreedy]),
+                    TransformHint::Execute(vec![("g", TransformHintPart::StringGreedy)]),
                 ),
             ])
         }),
@@ -129,3 +129,104 @@ fn transform_argument() {
         assert_eq!(result, fragments);
     }
 }
+
+#[test]
+fn transform_numeric_literal() {
+    let transformer = TokenTransformer::command_group(
+        |s| Ok(s),
+        TransformHint::Select({
+            HashMap::from_iter([
+                (
+                    "int",
+                    TransformHint::Execute(vec![("i", TransformHintPart::Integer)]),
+                ),
+                (
+                    "float",
+                    TransformHint::Execute(vec![("f", TransformHintPart::Float)]),
+                ),
+            ])
+        }),
+    );
+
+    for (command, value) in [
+        ("int 1_000", CommandArgumentValue::I64(1000)),
+        ("int -0xff", CommandArgumentValue::I64(-255)),
+        ("int 0o17", CommandArgumentValue::I64(15)),
+        ("int 0b101", CommandArgumentValue::I64(5)),
+        ("int 1e5", CommandArgumentValue::String("1e5".to_string())),
+        (
+            "int 99999999999999999999",
+            CommandArgumentValue::String("99999999999999999999".to_string()),
+        ),
+        ("float 1e5", CommandArgumentValue::F64(1e5)),
+        ("float 2.5E-3", CommandArgumentValue::F64(2.5e-3)),
+        ("float 1_000.5", CommandArgumentValue::F64(1000.5)),
+        ("float 0x10", CommandArgumentValue::F64(16.0)),
+    ] {
+        let (name, _) = command.split_once(' ').unwrap();
+        let lexer = CommandLexer::new(command);
+        let fragments: Result<Vec<_>, _> = transformer.transform(lexer).collect();
+        assert_eq!(
+            Ok(vec![
+                CommandFragment::Select(name.to_string()),
+                CommandFragment::Execute(vec![CommandArgument::Positioned(0, value)]),
+            ]),
+            fragments
+        );
+    }
+}
+
+#[test]
+fn split_command() {
+    let separators = [";", "&&", "\n"];
+    for (source, commands) in [
+        ("/mute @a; /mute @b", vec!["/mute @a", " /mute @b"]),
+        ("/mute @a;/mute @b;", vec!["/mute @a", "/mute @b"]),
+        ("/a && /b\n/c", vec!["/a ", " /b", "/c"]),
+        (
+            r#"/say "a; b" && /say it's;/say c='"\'; d'"#,
+            vec![r#"/say "a; b" "#, " /say it's", r#"/say c='"\'; d'"#],
+        ),
+        ("/say \"unclosed; /next", vec!["/say \"unclosed; /next"]),
+    ] {
+        assert_eq!(
+            commands,
+            split_commands(source, &separators).collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn transform_batch() {
+    let transformer = TokenTransformer::command_group(
+        |s| remove_leading("/", s),
+        TransformHint::Select(HashMap::from_iter([(
+            "mute",
+            TransformHint::Execute(vec![("s", TransformHintPart::String)]),
+        )])),
+    );
+
+    let mute = |target: &str| {
+        Ok(vec![
+            CommandFragment::Select("mute".to_string()),
+            CommandFragment::Execute(vec![CommandArgument::Positioned(
+                0,
+                CommandArgumentValue::String(target.to_string()),
+            )]),
+        ])
+    };
+    assert_eq!(
+        vec![
+            mute("@a"),
+            Err(TokenTransformError::InvalidCommandLabel),
+            mute("@c"),
+            mute("@d"),
+        ],
+        transformer
+            .transform_batch(
+                "/mute @a; mute @b && /mute @c\n/mute @d",
+                &[";", "&&", "\n"]
+            )
+            .collect::<Vec<_>>()
+    );
+}
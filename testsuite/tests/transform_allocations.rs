@@ -0,0 +1,64 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use kal::lex::{remove_leading, CommandLexer, TokenTransformer, TransformHint};
+use pretty_assertions::assert_eq;
+
+#[path = "../../kal/benches/common/mod.rs"]
+mod common;
+
+use common::{hint, MESSAGES};
+
+/// Count allocations made on the current thread, not to count the ones of test harness.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations_per_message(hint: TransformHint) -> Vec<usize> {
+    let transformer = TokenTransformer::command_group(|s| remove_leading("/", s), hint);
+    MESSAGES
+        .iter()
+        .map(|message| {
+            let before = ALLOCATIONS.with(Cell::get);
+            let fragments: Result<Vec<_>, _> =
+                transformer.transform(CommandLexer::new(message)).collect();
+            let after = ALLOCATIONS.with(Cell::get);
+            fragments.expect("message must transform");
+            after - before
+        })
+        .collect()
+}
+
+#[test]
+fn transform_borrows_hint() {
+    let narrow = allocations_per_message(hint(0));
+    let wide = allocations_per_message(hint(32));
+    // Cloning the hint per message would make the wide tree allocate a lot more.
+    assert_eq!(narrow, wide);
+    for (message, allocations) in MESSAGES.iter().zip(wide) {
+        assert!(
+            allocations <= 10,
+            "{:?} took {} allocations",
+            message,
+            allocations
+        );
+    }
+}
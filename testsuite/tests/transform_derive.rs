@@ -1,5 +1,5 @@
 use kal::{
-    lex::{CommandLexer, TokenTransformer, TransformHintProvider},
+    lex::{remove_leading, CommandLexer, TokenTransformer, TransformHint, TransformHintProvider},
     Command, CommandParseError,
 };
 use pretty_assertions::assert_eq;
@@ -36,3 +36,148 @@ fn transform() {
         parsed
     );
 }
+
+#[test]
+fn select_or_execute() {
+    #[derive(Debug, PartialEq, Command, TransformHintProvider)]
+    enum Roll {
+        #[command(self)]
+        Dice {
+            sides: Option<i64>,
+        },
+        Stats,
+        History {
+            count: i64,
+        },
+    }
+
+    #[derive(Debug, PartialEq, Command, TransformHintProvider)]
+    enum Root {
+        Roll(Roll),
+    }
+
+    let transformer = TokenTransformer::command_group(|s| remove_leading("/", s), Root::hint());
+    for (command, expected) in [
+        ("/roll 20", Root::Roll(Roll::Dice { sides: Some(20) })),
+        ("/roll", Root::Roll(Roll::Dice { sides: None })),
+        ("/roll sides=6", Root::Roll(Roll::Dice { sides: Some(6) })),
+        ("/roll stats", Root::Roll(Roll::Stats)),
+        ("/roll history 5", Root::Roll(Roll::History { count: 5 })),
+    ] {
+        let lexer = CommandLexer::new(command);
+        let fragments: Result<Vec<_>, _> = transformer.transform(lexer).collect();
+        let parsed = fragments
+            .as_ref()
+            .map_err(CommandParseError::from)
+            .and_then(|fragments| Root::parse(fragments));
+        assert_eq!(Ok(expected), parsed, "{}", command);
+    }
+}
+
+#[test]
+fn named_argument_hint() {
+    #[derive(Debug, PartialEq, Command, TransformHintProvider)]
+    struct Repeat {
+        text: String,
+        count: i64,
+        ratio: Option<f64>,
+    }
+
+    let transformer = TokenTransformer::command_args(Repeat::hint());
+    for command in [
+        "hello 3",
+        "hello count=3",
+        "count=3 text=hello",
+        "ratio=0.5 count=3 text=hello",
+    ] {
+        let lexer = CommandLexer::new(command);
+        let fragments: Result<Vec<_>, _> = transformer.transform(lexer).collect();
+        let parsed = fragments
+            .as_ref()
+            .map_err(CommandParseError::from)
+            .and_then(|fragments| Repeat::parse(fragments));
+        let ratio = command.contains("ratio").then_some(0.5);
+        assert_eq!(
+            Ok(Repeat {
+                text: "hello".to_string(),
+                count: 3,
+                ratio,
+            }),
+            parsed,
+            "{}",
+            command
+        );
+    }
+}
+
+#[test]
+fn hint_from_spec() {
+    #[derive(Command, TransformHintProvider)]
+    #[allow(dead_code)]
+    struct Note {
+        title: String,
+        #[argument(take_rest)]
+        body: Option<String>,
+    }
+
+    #[derive(Command, TransformHintProvider)]
+    #[allow(dead_code)]
+    enum Roll {
+        #[command(self)]
+        Dice {
+            sides: Option<i64>,
+        },
+        Stats,
+        Scale {
+            factor: f64,
+        },
+        Note(Note),
+    }
+
+    assert_eq!(Note::hint(), TransformHint::from(&Note::spec()));
+    assert_eq!(Roll::hint(), TransformHint::from(&Roll::spec()));
+    assert_eq!(Roll::hint(), TransformHint::from(Roll::spec_ref()));
+}
+
+#[test]
+fn command_group_hint() {
+    #[derive(Debug, PartialEq, Command, TransformHintProvider)]
+    struct Echo {
+        #[argument(take_rest)]
+        text: String,
+    }
+
+    #[derive(Debug, PartialEq, Command)]
+    struct Add {
+        a: i64,
+        b: i64,
+    }
+
+    kal::command_group! {
+        #[derive(Debug, PartialEq)]
+        enum Root {
+            #[hint_provider]
+            Echo(Echo),
+            Add(Add),
+        }
+    }
+
+    let transformer = TokenTransformer::command_group(|s| remove_leading("/", s), Root::hint());
+    for (command, expected) in [
+        (
+            "/echo hello world",
+            Root::Echo(Echo {
+                text: "hello world".to_string(),
+            }),
+        ),
+        ("/add 1 2", Root::Add(Add { a: 1, b: 2 })),
+    ] {
+        let lexer = CommandLexer::new(command);
+        let fragments: Result<Vec<_>, _> = transformer.transform(lexer).collect();
+        let parsed = fragments
+            .as_ref()
+            .map_err(CommandParseError::from)
+            .and_then(|fragments| Root::parse(fragments));
+        assert_eq!(Ok(expected), parsed, "{}", command);
+    }
+}
@@ -0,0 +1,242 @@
+use kal::{
+    Arguments, CommaSeparated, Command, CommandArgument, CommandArgumentValue, CommandFragment,
+    CommandOptionPattern, CommandOptionValidation, CommandParseError,
+};
+use pretty_assertions::assert_eq;
+
+fn not_admin(name: &String) -> Result<(), String> {
+    if name == "admin" {
+        Err("cannot be admin".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Arguments)]
+struct Target {
+    #[argument(pattern = "^[a-z]+$", validate = "not_admin")]
+    user: String,
+}
+
+#[derive(Debug, PartialEq, Command)]
+struct Purge {
+    #[argument(min = 1, max = 100)]
+    count: i64,
+    #[argument(flatten)]
+    target: Target,
+    #[argument(min_length = 3, max_length = 10)]
+    reason: Option<String>,
+    #[argument(min = "-1.5", max = 1.5)]
+    ratio: Option<f64>,
+}
+
+fn parse(arguments: Vec<CommandArgumentValue>) -> Result<Purge, String> {
+    let arguments = arguments
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| CommandArgument::Positioned(i, value))
+        .collect();
+    Purge::parse(&[CommandFragment::Execute(arguments)]).map_err(|e| e.to_string())
+}
+
+#[test]
+fn validation_spec() {
+    let spec = Purge::spec();
+    assert_eq!(
+        spec.options[0].validation,
+        CommandOptionValidation {
+            min_value: Some(1.0),
+            max_value: Some(100.0),
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        spec.options[1].validation,
+        CommandOptionValidation {
+            pattern: Some(CommandOptionPattern::new("^[a-z]+$")),
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        spec.options[2].validation,
+        CommandOptionValidation {
+            min_length: Some(3),
+            max_length: Some(10),
+            ..Default::default()
+        }
+    );
+    assert_eq!(spec.options[3].validation.min_value, Some(-1.5));
+}
+
+#[test]
+fn validation_parse() {
+    use CommandArgumentValue::{String as S, F64, I64};
+
+    assert_eq!(
+        Ok(Purge {
+            count: 10,
+            target: Target {
+                user: "alice".to_string()
+            },
+            reason: Some("spam".to_string()),
+            ratio: Some(-1.0),
+        }),
+        parse(vec![
+            I64(10),
+            S("alice".into()),
+            S("spam".into()),
+            F64(-1.0)
+        ])
+    );
+
+    for (arguments, error) in [
+        (
+            vec![I64(0), S("alice".into())],
+            "Invalid argument count: must be at least 1",
+        ),
+        (
+            vec![I64(101), S("alice".into())],
+            "Invalid argument count: must be at most 100",
+        ),
+        (
+            vec![I64(1), S("Alice".into())],
+            "Invalid argument user: must match pattern `^[a-z]+$`",
+        ),
+        (
+            vec![I64(1), S("admin".into())],
+            "Invalid argument user: cannot be admin",
+        ),
+        (
+            vec![I64(1), S("alice".into()), S("no".into())],
+            "Invalid argument reason: must be at least 3 characters long",
+        ),
+        (
+            vec![I64(1), S("alice".into()), S("spam".repeat(3))],
+            "Invalid argument reason: must be at most 10 characters long",
+        ),
+        (
+            vec![I64(1), S("alice".into()), S("spam".into()), F64(2.0)],
+            "Invalid argument ratio: must be at most 1.5",
+        ),
+    ] {
+        assert_eq!(Err(error.to_string()), parse(arguments));
+    }
+
+    assert_eq!(
+        Err(CommandParseError::ValidationFailed {
+            argument: "count",
+            reason: "must be at least 1".to_string(),
+        }),
+        Purge::parse(&[CommandFragment::Execute(vec![CommandArgument::Named(
+            "count".to_string(),
+            I64(-3)
+        )])])
+    );
+}
+
+#[derive(Debug, PartialEq, Command)]
+struct Roll {
+    #[argument(min = 1, max = 6)]
+    dice: CommaSeparated<i64>,
+    #[argument(max = 9007199254740992)]
+    seed: Option<i64>,
+    #[argument(variadic, min_length = 2)]
+    labels: Vec<String>,
+}
+
+#[test]
+fn validation_each_element() {
+    use CommandArgumentValue::{String as S, I64};
+
+    let parse = |arguments: Vec<CommandArgumentValue>| {
+        let arguments = arguments
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| CommandArgument::Positioned(i, value))
+            .collect();
+        Roll::parse(&[CommandFragment::Execute(arguments)]).map_err(|e| e.to_string())
+    };
+
+    assert_eq!(
+        Ok(Roll {
+            dice: CommaSeparated(vec![1, 6]),
+            seed: Some(9007199254740992),
+            labels: vec!["ab".to_string(), "cd".to_string()],
+        }),
+        parse(vec![
+            S("1,6".into()),
+            I64(9007199254740992),
+            S("ab".into()),
+            S("cd".into())
+        ])
+    );
+    for (arguments, error) in [
+        (
+            vec![S("1,7".into())],
+            "Invalid argument dice: must be at most 6",
+        ),
+        (
+            vec![S("0,6".into())],
+            "Invalid argument dice: must be at least 1",
+        ),
+        (
+            // It would be rounded down to the bound as f64.
+            vec![S("1".into()), I64(9007199254740993)],
+            "Invalid argument seed: must be at most 9007199254740992",
+        ),
+        (
+            vec![S("1".into()), I64(1), S("ab".into()), S("c".into())],
+            "Invalid argument labels: must be at least 2 characters long",
+        ),
+    ] {
+        assert_eq!(Err(error.to_string()), parse(arguments));
+    }
+}
+
+mod checks {
+    pub fn even(value: &i64) -> Result<(), String> {
+        if value % 2 == 0 {
+            Ok(())
+        } else {
+            Err("must be even".to_string())
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Command)]
+struct Pair {
+    #[argument(min = 2, validate = checks::even)]
+    size: i64,
+    #[argument(validate = not_admin)]
+    owner: String,
+}
+
+#[test]
+fn validation_unquoted_path() {
+    use CommandArgumentValue::{String as S, I64};
+
+    let parse = |arguments: Vec<CommandArgumentValue>| {
+        let arguments = arguments
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| CommandArgument::Positioned(i, value))
+            .collect();
+        Pair::parse(&[CommandFragment::Execute(arguments)]).map_err(|e| e.to_string())
+    };
+
+    assert_eq!(
+        Ok(Pair {
+            size: 4,
+            owner: "alice".to_string(),
+        }),
+        parse(vec![I64(4), S("alice".into())])
+    );
+    assert_eq!(
+        Err("Invalid argument size: must be even".to_string()),
+        parse(vec![I64(3), S("alice".into())])
+    );
+    assert_eq!(
+        Err("Invalid argument owner: cannot be admin".to_string()),
+        parse(vec![I64(4), S("admin".into())])
+    );
+}
@@ -0,0 +1,110 @@
+use kal::{
+    lex::{
+        render, CommandLexer, RenderStyle, TokenTransformer, TransformHint, TransformHintPart,
+        TransformHintProvider,
+    },
+    Command, CommandArgument, CommandArgumentValue, CommandFragment, CommandOption,
+    CommandOptionValueKind, CommandParseError, ToFragments,
+};
+use pretty_assertions::assert_eq;
+
+#[derive(Debug, PartialEq, Command, TransformHintProvider)]
+#[command(to_fragments)]
+enum Mod {
+    Kick {
+        reason: String,
+        #[argument(variadic)]
+        targets: Vec<String>,
+    },
+    Roll {
+        #[argument(variadic)]
+        dice: Vec<i64>,
+    },
+}
+
+#[test]
+fn variadic_spec() {
+    assert_eq!(
+        Mod::spec().subcommands[0].options[1],
+        CommandOption {
+            name: "targets",
+            position: 1,
+            description: "",
+            value: CommandOptionValueKind::Multiple(Box::new(CommandOptionValueKind::String)),
+            take_rest: false,
+            variadic: true,
+            validation: Default::default(),
+            requires: vec![],
+            completion_provider: None,
+        }
+    );
+    let hint = Mod::hint();
+    assert_eq!(hint, TransformHint::from(&Mod::spec()));
+    let TransformHint::Select(map) = hint else {
+        panic!("expected select hint");
+    };
+    assert_eq!(
+        map["roll"],
+        TransformHint::Execute(vec![("dice", TransformHintPart::Integer.make_variadic())])
+    );
+}
+
+#[test]
+fn variadic_parse() {
+    let transformer = TokenTransformer::command_args(Mod::hint());
+    for (command, expected) in [
+        (
+            "kick spam a b c",
+            Mod::Kick {
+                reason: "spam".to_string(),
+                targets: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            },
+        ),
+        (
+            "kick spam",
+            Mod::Kick {
+                reason: "spam".to_string(),
+                targets: vec![],
+            },
+        ),
+        (
+            "roll 4 6 0x14",
+            Mod::Roll {
+                dice: vec![4, 6, 20],
+            },
+        ),
+    ] {
+        let lexer = CommandLexer::new(command);
+        let fragments: Result<Vec<_>, _> = transformer.transform(lexer).collect();
+        let parsed = fragments
+            .as_ref()
+            .map_err(CommandParseError::from)
+            .and_then(|fragments| Mod::parse(fragments));
+        assert_eq!(Ok(&expected), parsed.as_ref(), "{}", command);
+
+        let hint = Mod::hint();
+        let style = RenderStyle {
+            hint: Some(&hint),
+            ..RenderStyle::default()
+        };
+        let rendered = render(&expected.to_fragments(), style);
+        let lexer = CommandLexer::new(&rendered);
+        let fragments: Result<Vec<_>, _> = transformer.transform(lexer).collect();
+        let reparsed = fragments
+            .as_ref()
+            .map_err(CommandParseError::from)
+            .and_then(|fragments| Mod::parse(fragments));
+        assert_eq!(Ok(&expected), reparsed.as_ref(), "{}", rendered);
+    }
+
+    assert_eq!(
+        Ok(Mod::Roll { dice: vec![1, 2] }),
+        Mod::parse(&[
+            CommandFragment::Select("roll".to_string()),
+            CommandFragment::Execute(vec![
+                CommandArgument::Positioned(0, CommandArgumentValue::I64(1)),
+                CommandArgument::Positioned(1, CommandArgumentValue::I64(2)),
+            ]),
+        ])
+    );
+}